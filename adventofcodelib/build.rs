@@ -0,0 +1,135 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// the year of the input files stored directly in the input directory
+const FLAT_LAYOUT_YEAR: u16 = 2018;
+
+///
+/// Parse the day of the input file name. Example: `day1_data.txt` -> 1
+///
+fn input_day(file_name: &str) -> Option<u8> {
+    file_name
+        .strip_prefix("day")?
+        .strip_suffix("_data.txt")?
+        .parse()
+        .ok()
+}
+
+///
+/// Collect the input files of the directory as (year, day, path), the encrypted ones
+/// separately.
+///
+fn collect_inputs(
+    directory: &Path,
+    year: u16,
+    inputs: &mut Vec<(u16, u8, PathBuf)>,
+    encrypted: &mut Vec<(u16, u8, PathBuf)>,
+) {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if path.is_dir() {
+            // the inputs of other years live in `input_data/<year>/`
+            if let Ok(year) = name.parse::<u16>() {
+                collect_inputs(&path, year, inputs, encrypted);
+            }
+        } else if let Some(day) = input_day(&name) {
+            inputs.push((year, day, path));
+        } else if let Some(day) = name.strip_suffix(".enc").and_then(input_day) {
+            encrypted.push((year, day, path));
+        }
+    }
+}
+
+// the sources the answers depend on, relative to the library directory; the other modules
+// (the leaderboard, the rendering, the configuration...) do not change the cached answers
+const SOLVER_SOURCES: [&str; 9] = [
+    "src/lib.rs",
+    "src/solutions.rs",
+    "src/checksum.rs",
+    "src/fabric.rs",
+    "src/frequency.rs",
+    "src/fuzzy.rs",
+    "src/line.rs",
+    "src/sweep.rs",
+    "../adventofcodelib_derive/src/lib.rs",
+];
+
+///
+/// Hash the solver sources, the solver version of the answer cache.
+///
+fn solver_version() -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325;
+    for source in SOLVER_SOURCES.iter() {
+        println!("cargo:rerun-if-changed={}", source);
+        // FNV-1a
+        for byte in fs::read(source).unwrap() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+fn embed_inputs() {
+    let input_root = match env::var_os("AOC_EMBED_INPUT_ROOT") {
+        Some(root) => PathBuf::from(root),
+        None => Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("../input_data"),
+    };
+    println!("cargo:rerun-if-changed={}", input_root.display());
+    let (mut inputs, mut encrypted) = (Vec::new(), Vec::new());
+    collect_inputs(&input_root, FLAT_LAYOUT_YEAR, &mut inputs, &mut encrypted);
+    inputs.sort();
+    encrypted.sort();
+    // the inputs are embedded as they are, the encrypted ones must be decrypted first
+    for (year, day, path) in encrypted {
+        if !inputs.iter().any(|&(y, d, _)| (y, d) == (year, day)) {
+            println!(
+                "cargo:warning=the input of {} day {} is not embedded, '{}' is encrypted: \
+                 run `adventofcode decrypt` first",
+                year,
+                day,
+                path.display()
+            );
+        }
+    }
+    let mut code = String::new();
+    if inputs.is_empty() {
+        code.push_str(&format!(
+            "compile_error!({:?});\n",
+            format!(
+                "the embed-inputs feature found no input files in '{}'",
+                input_root.display()
+            )
+        ));
+    }
+    code.push_str("pub static INPUTS: &[(u16, u8, &str)] = &[\n");
+    for (year, day, path) in inputs {
+        let path = fs::canonicalize(&path).unwrap();
+        println!("cargo:rerun-if-changed={}", path.display());
+        code.push_str(&format!(
+            "    ({}, {}, include_str!({:?})),\n",
+            year, day, path
+        ));
+    }
+    code.push_str("];\n");
+    let out_file = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded_inputs.rs");
+    fs::write(out_file, code).unwrap();
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=AOC_EMBED_INPUT_ROOT");
+    println!(
+        "cargo:rustc-env=AOC_SOLVER_VERSION={:016x}",
+        solver_version()
+    );
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_some() {
+        embed_inputs();
+    }
+}
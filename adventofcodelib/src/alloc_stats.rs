@@ -0,0 +1,139 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+///
+/// The system allocator which counts allocations and tracks the heap size.
+/// The binary opts in by installing it:
+///
+/// #[global_allocator]
+/// static ALLOCATOR: CountingAllocator = CountingAllocator;
+///
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record_allocation(size: usize) {
+        ENABLED.store(true, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record_deallocation(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            CountingAllocator::record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            CountingAllocator::record_allocation(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CountingAllocator::record_deallocation(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            CountingAllocator::record_deallocation(layout.size());
+            CountingAllocator::record_allocation(new_size);
+        }
+        new_ptr
+    }
+}
+
+///
+/// Heap usage of a measured piece of code
+///
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AllocStats {
+    /// count of allocations and reallocations
+    pub allocations: usize,
+    /// total size of all the allocations in bytes
+    pub allocated: usize,
+    /// the largest heap growth over the heap size at the start, in bytes
+    pub peak: usize,
+}
+
+///
+/// Is the counting allocator installed as the global allocator
+///
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+///
+/// Run the function and count its heap usage. Returns `None` statistics when the counting
+/// allocator is not installed. The counters are global, so allocations made by other threads
+/// at the same time are counted too.
+///
+/// # Arguments
+///
+/// * `f` - the measured function.
+///
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Option<AllocStats>) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+    let current = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(current, Ordering::Relaxed);
+    let result = f();
+    if !is_enabled() {
+        return (result, None);
+    }
+    let stats = AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        allocated: ALLOCATED.load(Ordering::Relaxed) - allocated,
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(current),
+    };
+    (result, Some(stats))
+}
+
+///
+/// Format a size in bytes for humans. Example: `1.5 MiB`
+///
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+impl std::fmt::Display for AllocStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocs, {} allocated, {} peak",
+            self.allocations,
+            format_bytes(self.allocated),
+            format_bytes(self.peak)
+        )
+    }
+}
@@ -0,0 +1,185 @@
+use crate::solutions::{Solution, SOLVER_VERSION};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+///
+/// FNV-1a hash of the input content
+///
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+///
+/// What a cached answer depends on
+///
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CacheKey {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    /// FNV-1a hash of the input content
+    pub input_hash: u64,
+    /// the solver version
+    pub version: String,
+}
+
+impl CacheKey {
+    ///
+    /// The key of the solution for its current input. Fails when the input cannot be read.
+    ///
+    pub fn of(solution: &Solution) -> Result<Self, String> {
+        let input = crate::input_content(&solution.file_name)?;
+        Ok(CacheKey {
+            year: solution.year,
+            day: solution.day,
+            part: solution.part,
+            input_hash: fnv1a(input.as_bytes()),
+            version: solution.version.to_string(),
+        })
+    }
+
+    ///
+    /// Is it the same task and input, maybe solved by another solver version
+    ///
+    pub fn same_input(&self, other: &CacheKey) -> bool {
+        self.year == other.year
+            && self.day == other.day
+            && self.part == other.part
+            && self.input_hash == other.input_hash
+    }
+}
+
+///
+/// A stored answer with the time it took to find it
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedAnswer {
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+///
+/// The answers of the previous runs stored in a file. Line example
+/// (tab separated): `2018 1 1 cbf29ce484222325 3f2a9c01d5e6b7a8 138450 520`
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnswerCache {
+    file: PathBuf,
+    entries: BTreeMap<CacheKey, CachedAnswer>,
+}
+
+impl AnswerCache {
+    ///
+    /// Load the cache file. A missing file is an empty cache.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - a path to the cache file.
+    ///
+    pub fn load(file: &Path) -> io::Result<Self> {
+        let mut cache = AnswerCache {
+            file: file.to_path_buf(),
+            entries: BTreeMap::new(),
+        };
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(cache),
+            Err(e) => return Err(e),
+        };
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, answer) = parse_line(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Cannot parse string '{}' in '{}'", line, file.display()),
+                )
+            })?;
+            cache.entries.insert(key, answer);
+        }
+        Ok(cache)
+    }
+
+    ///
+    /// Write the cache file, creating its directory. The answers of the old solver versions
+    /// are dropped when the current version has solved the same task and input.
+    ///
+    pub fn save(&self) -> io::Result<()> {
+        if let Some(directory) = self.file.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let is_stale = |key: &CacheKey| {
+            key.version != SOLVER_VERSION
+                && self
+                    .entries
+                    .keys()
+                    .any(|k| k.version == SOLVER_VERSION && k.same_input(key))
+        };
+        let content = self
+            .entries
+            .iter()
+            .filter(|(key, _)| !is_stale(key))
+            .map(|(key, answer)| {
+                format!(
+                    "{}\t{}\t{}\t{:016x}\t{}\t{}\t{}\n",
+                    key.year,
+                    key.day,
+                    key.part,
+                    key.input_hash,
+                    key.version,
+                    answer.elapsed.as_nanos(),
+                    answer.answer
+                )
+            })
+            .collect::<String>();
+        fs::write(&self.file, content)
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<&CachedAnswer> {
+        self.entries.get(key)
+    }
+
+    pub fn insert(&mut self, key: CacheKey, answer: CachedAnswer) {
+        self.entries.insert(key, answer);
+    }
+
+    ///
+    /// The answers for the same task and input found by other solver versions.
+    ///
+    pub fn other_versions<'a>(
+        &'a self,
+        key: &'a CacheKey,
+    ) -> impl Iterator<Item = (&'a CacheKey, &'a CachedAnswer)> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(k, _)| k.same_input(key) && k.version != key.version)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn parse_line(line: &str) -> Option<(CacheKey, CachedAnswer)> {
+    let mut fields = line.splitn(7, '\t');
+    let key = CacheKey {
+        year: fields.next()?.parse().ok()?,
+        day: fields.next()?.parse().ok()?,
+        part: fields.next()?.parse().ok()?,
+        input_hash: u64::from_str_radix(fields.next()?, 16).ok()?,
+        version: fields.next()?.to_string(),
+    };
+    let elapsed = Duration::from_nanos(fields.next()?.parse().ok()?);
+    let answer = fields.next()?.to_string();
+    Some((key, CachedAnswer { answer, elapsed }))
+}
@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+///
+/// Shared flag asking a running solution to stop
+///
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+///
+/// Run the function with the token installed for the current thread, so the solutions
+/// called by the function can see the cancellation through `is_cancelled`.
+///
+/// # Arguments
+///
+/// * `token` - the token to install.
+/// * `f` - the function to run.
+///
+pub fn with_token<T, F: FnOnce() -> T>(token: CancellationToken, f: F) -> T {
+    let previous = CURRENT.with(|current| current.replace(Some(token)));
+    let result = f();
+    CURRENT.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+///
+/// Has the current thread been asked to stop. Long or possibly endless loops of the solutions
+/// check it and return early; whatever they return after the cancellation is discarded.
+/// Always false when no token is installed.
+///
+pub fn is_cancelled() -> bool {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    })
+}
@@ -0,0 +1,108 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+///
+/// What the letters of a box id are: Unicode scalar values or bytes
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    Chars,
+    Bytes,
+}
+
+///
+/// How many distinct letters of the box id appear exactly N times, by N
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiplicityProfile {
+    pub id: String,
+    pub letters: BTreeMap<usize, usize>,
+}
+
+impl MultiplicityProfile {
+    ///
+    /// Count the letters of the id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the box id. Example: `bababc` has one letter twice and one letter three times.
+    /// * `units` - count chars or bytes.
+    ///
+    pub fn of(id: &str, units: Units) -> Self {
+        let counts = match units {
+            Units::Chars => multiplicities(id.chars()),
+            Units::Bytes => multiplicities(id.bytes()),
+        };
+        MultiplicityProfile {
+            id: id.to_string(),
+            letters: counts,
+        }
+    }
+
+    ///
+    /// Does some letter appear exactly `multiplicity` times
+    ///
+    pub fn has(&self, multiplicity: usize) -> bool {
+        self.letters.contains_key(&multiplicity)
+    }
+}
+
+fn multiplicities<T: Hash + Eq>(letters: impl Iterator<Item = T>) -> BTreeMap<usize, usize> {
+    let mut histogram: HashMap<T, usize> = HashMap::new();
+    for letter in letters {
+        *histogram.entry(letter).or_insert(0) += 1;
+    }
+    let mut counts = BTreeMap::new();
+    for count in histogram.into_values() {
+        *counts.entry(count).or_insert(0) += 1;
+    }
+    counts
+}
+
+///
+/// The checksum of the box ids for a set of multiplicities
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checksum {
+    /// the multiplicities the checksum is made of. Example: `[2, 3]`
+    pub multiplicities: Vec<usize>,
+    /// the number of the ids having a letter exactly N times for every multiplicity N
+    pub counts: Vec<u64>,
+    pub profiles: Vec<MultiplicityProfile>,
+}
+
+impl Checksum {
+    ///
+    /// Profile the box ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids` - the box ids.
+    /// * `multiplicities` - the multiplicities to count the ids by. Example: `[2, 3]`
+    /// * `units` - count chars or bytes.
+    ///
+    pub fn new(ids: &[String], multiplicities: &[usize], units: Units) -> Self {
+        let profiles = ids
+            .iter()
+            .map(|id| MultiplicityProfile::of(id, units))
+            .collect::<Vec<MultiplicityProfile>>();
+        let counts = multiplicities
+            .iter()
+            .map(|&m| profiles.iter().filter(|p| p.has(m)).count() as u64)
+            .collect();
+        Checksum {
+            multiplicities: multiplicities.to_vec(),
+            counts,
+            profiles,
+        }
+    }
+
+    ///
+    /// The product of the counts, `None` when it overflows
+    ///
+    pub fn value(&self) -> Option<u64> {
+        self.counts
+            .iter()
+            .try_fold(1u64, |product, &count| product.checked_mul(count))
+    }
+}
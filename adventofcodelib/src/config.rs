@@ -0,0 +1,430 @@
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+///
+/// Name of the project configuration file
+///
+pub const CONFIG_FILE_NAME: &str = "aoc.toml";
+
+///
+/// Where the effective value of a setting comes from
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Default,
+    File,
+    Env(&'static str),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File => write!(f, "config file"),
+            Source::Env(name) => write!(f, "${}", name),
+        }
+    }
+}
+
+///
+/// How the commands print their results
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Csv,
+}
+
+impl OutputFormat {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "text" => Ok(OutputFormat::Text),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!(
+                "Unknown output format '{}', expected text or csv",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+///
+/// Where the adventofcode.com session token is read from
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionSource {
+    /// the name of the environment variable holding the token
+    Env(String),
+    /// the file holding the token
+    File(PathBuf),
+}
+
+impl fmt::Display for SessionSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionSource::Env(name) => write!(f, "env {}", name),
+            SessionSource::File(path) => write!(f, "file {}", path.display()),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct SessionFile {
+    env: Option<String>,
+    file: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    input_root: Option<PathBuf>,
+    default_year: Option<u16>,
+    output_format: Option<String>,
+    /// seconds, 0 for no limit
+    timeout: Option<u64>,
+    parallelism: Option<usize>,
+    cache_file: Option<PathBuf>,
+    session: Option<SessionFile>,
+}
+
+///
+/// A setting with the place its value comes from
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+impl<T> Setting<T> {
+    fn default(value: T) -> Self {
+        Setting {
+            value,
+            source: Source::Default,
+        }
+    }
+
+    fn set(&mut self, value: T, source: Source) {
+        self.value = value;
+        self.source = source;
+    }
+}
+
+///
+/// The effective project configuration: the defaults, overridden by `aoc.toml`,
+/// overridden by the `AOC_*` environment variables.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// the configuration file the settings were read from
+    pub file: Option<PathBuf>,
+    pub input_root: Setting<PathBuf>,
+    pub default_year: Setting<u16>,
+    pub session: Setting<SessionSource>,
+    pub output_format: Setting<OutputFormat>,
+    /// the time limit of a single solution run, `None` for no limit
+    pub timeout: Setting<Option<Duration>>,
+    /// how many solutions run at the same time
+    pub parallelism: Setting<usize>,
+    /// the file with the answers of the previous runs
+    pub cache_file: Setting<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            file: None,
+            input_root: Setting::default(PathBuf::from(crate::INPUT_ROOT)),
+            default_year: Setting::default(2018),
+            session: Setting::default(SessionSource::Env(String::from("AOC_SESSION"))),
+            output_format: Setting::default(OutputFormat::Text),
+            timeout: Setting::default(Some(Duration::from_secs(60))),
+            parallelism: Setting::default(1),
+            cache_file: Setting::default(PathBuf::from(".aoc_cache/answers.tsv")),
+        }
+    }
+}
+
+fn timeout_from_secs(seconds: u64) -> Option<Duration> {
+    if seconds == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(seconds))
+    }
+}
+
+///
+/// Find the configuration file: `$AOC_CONFIG` if set, otherwise `aoc.toml` in the start
+/// directory or the closest of its parents, otherwise `aoc/aoc.toml` in the user
+/// configuration directory (`$XDG_CONFIG_HOME`, `~/.config` by default).
+///
+/// # Arguments
+///
+/// * `start` - the directory to search from, usually the current directory.
+/// * `var` - reads an environment variable.
+///
+pub fn discover<F: Fn(&str) -> Option<String>>(start: &Path, var: F) -> Option<PathBuf> {
+    if let Some(path) = var("AOC_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    for directory in start.ancestors() {
+        let candidate = directory.join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+    let candidate = user_config_dir(var)?.join(CONFIG_FILE_NAME);
+    if candidate.is_file() {
+        Some(candidate)
+    } else {
+        None
+    }
+}
+
+///
+/// The user configuration directory of the project: `$XDG_CONFIG_HOME/aoc`,
+/// `~/.config/aoc` by default.
+///
+/// # Arguments
+///
+/// * `var` - reads an environment variable.
+///
+pub fn user_config_dir<F: Fn(&str) -> Option<String>>(var: F) -> Option<PathBuf> {
+    let config_home = var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join("aoc"))
+}
+
+impl Config {
+    ///
+    /// Load the configuration for the current directory and environment.
+    ///
+    pub fn load() -> Result<Config, String> {
+        let current_dir =
+            env::current_dir().map_err(|e| format!("Cannot get current directory: {}", e))?;
+        let var = |name: &str| env::var(name).ok();
+        let mut config = Config::default();
+        if let Some(file) = discover(&current_dir, var) {
+            config.apply_file(&file)?;
+        }
+        config.apply_env(var)?;
+        Ok(config)
+    }
+
+    ///
+    /// Apply the settings of the configuration file. Relative paths are resolved against
+    /// the directory of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - a path to the configuration file.
+    ///
+    pub fn apply_file(&mut self, file: &Path) -> Result<(), String> {
+        let text = fs::read_to_string(file)
+            .map_err(|e| format!("Cannot read '{}': {}", file.display(), e))?;
+        let base = file.parent().unwrap_or_else(|| Path::new("."));
+        self.apply_toml(&text, base)
+            .map_err(|e| format!("Cannot parse '{}': {}", file.display(), e))?;
+        self.file = Some(file.to_path_buf());
+        Ok(())
+    }
+
+    ///
+    /// Apply the settings of the configuration file content.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - the TOML configuration. Example: `input_root = "input_data"`
+    /// * `base` - the directory relative paths are resolved against.
+    ///
+    pub fn apply_toml(&mut self, text: &str, base: &Path) -> Result<(), String> {
+        let file: ConfigFile = toml::from_str(text).map_err(|e| e.to_string())?;
+        if let Some(input_root) = file.input_root {
+            self.input_root.set(base.join(input_root), Source::File);
+        }
+        if let Some(year) = file.default_year {
+            self.default_year.set(year, Source::File);
+        }
+        if let Some(format) = file.output_format {
+            self.output_format
+                .set(OutputFormat::parse(&format)?, Source::File);
+        }
+        if let Some(seconds) = file.timeout {
+            self.timeout.set(timeout_from_secs(seconds), Source::File);
+        }
+        if let Some(parallelism) = file.parallelism {
+            if parallelism == 0 {
+                return Err(String::from("parallelism must be positive"));
+            }
+            self.parallelism.set(parallelism, Source::File);
+        }
+        if let Some(cache_file) = file.cache_file {
+            self.cache_file.set(base.join(cache_file), Source::File);
+        }
+        match file.session.unwrap_or_default() {
+            SessionFile {
+                env: Some(_),
+                file: Some(_),
+            } => return Err(String::from("session takes either env or file, not both")),
+            SessionFile {
+                env: Some(name), ..
+            } => self.session.set(SessionSource::Env(name), Source::File),
+            SessionFile {
+                file: Some(path), ..
+            } => self
+                .session
+                .set(SessionSource::File(base.join(path)), Source::File),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    ///
+    /// Apply the `AOC_INPUT_ROOT`, `AOC_YEAR`, `AOC_SESSION_FILE`, `AOC_OUTPUT_FORMAT`,
+    /// `AOC_TIMEOUT`, `AOC_PARALLELISM` and `AOC_CACHE_FILE` environment variables.
+    ///
+    /// # Arguments
+    ///
+    /// * `var` - reads an environment variable.
+    ///
+    pub fn apply_env<F: Fn(&str) -> Option<String>>(&mut self, var: F) -> Result<(), String> {
+        let invalid = |name: &str, value: &str| format!("Invalid ${} value '{}'", name, value);
+        if let Some(value) = var("AOC_INPUT_ROOT") {
+            self.input_root
+                .set(PathBuf::from(value), Source::Env("AOC_INPUT_ROOT"));
+        }
+        if let Some(value) = var("AOC_YEAR") {
+            let year = value
+                .parse::<u16>()
+                .map_err(|_| invalid("AOC_YEAR", &value))?;
+            self.default_year.set(year, Source::Env("AOC_YEAR"));
+        }
+        if let Some(value) = var("AOC_SESSION_FILE") {
+            self.session.set(
+                SessionSource::File(PathBuf::from(value)),
+                Source::Env("AOC_SESSION_FILE"),
+            );
+        }
+        if let Some(value) = var("AOC_OUTPUT_FORMAT") {
+            self.output_format.set(
+                OutputFormat::parse(&value)?,
+                Source::Env("AOC_OUTPUT_FORMAT"),
+            );
+        }
+        if let Some(value) = var("AOC_TIMEOUT") {
+            let seconds = value
+                .parse::<u64>()
+                .map_err(|_| invalid("AOC_TIMEOUT", &value))?;
+            self.timeout
+                .set(timeout_from_secs(seconds), Source::Env("AOC_TIMEOUT"));
+        }
+        if let Some(value) = var("AOC_PARALLELISM") {
+            let parallelism = value
+                .parse::<usize>()
+                .ok()
+                .filter(|&p| p > 0)
+                .ok_or_else(|| invalid("AOC_PARALLELISM", &value))?;
+            self.parallelism
+                .set(parallelism, Source::Env("AOC_PARALLELISM"));
+        }
+        if let Some(value) = var("AOC_CACHE_FILE") {
+            self.cache_file
+                .set(PathBuf::from(value), Source::Env("AOC_CACHE_FILE"));
+        }
+        Ok(())
+    }
+
+    ///
+    /// The input root as the string the solutions take.
+    ///
+    pub fn input_root(&self) -> String {
+        self.input_root.value.to_string_lossy().into_owned()
+    }
+
+    ///
+    /// Read the adventofcode.com session token. Returns `None` when it is not set.
+    ///
+    pub fn session_token(&self) -> Option<String> {
+        let token = match &self.session.value {
+            SessionSource::Env(name) => env::var(name).ok(),
+            SessionSource::File(path) => fs::read_to_string(path).ok(),
+        };
+        token
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+    }
+
+    ///
+    /// The effective settings, one per line, with the place each value comes from.
+    ///
+    pub fn show(&self) -> String {
+        let timeout = match self.timeout.value {
+            Some(limit) => format!("{}s", limit.as_secs()),
+            None => String::from("none"),
+        };
+        let lines = vec![
+            (
+                "input_root",
+                self.input_root.value.display().to_string(),
+                self.input_root.source,
+            ),
+            (
+                "default_year",
+                self.default_year.value.to_string(),
+                self.default_year.source,
+            ),
+            (
+                "session",
+                format!(
+                    "{} ({})",
+                    self.session.value,
+                    if self.session_token().is_some() {
+                        "token set"
+                    } else {
+                        "no token"
+                    }
+                ),
+                self.session.source,
+            ),
+            (
+                "output_format",
+                self.output_format.value.to_string(),
+                self.output_format.source,
+            ),
+            ("timeout", timeout, self.timeout.source),
+            (
+                "parallelism",
+                self.parallelism.value.to_string(),
+                self.parallelism.source,
+            ),
+            (
+                "cache_file",
+                self.cache_file.value.display().to_string(),
+                self.cache_file.source,
+            ),
+        ];
+        let mut result = match &self.file {
+            Some(file) => format!("# config file: {}\n", file.display()),
+            None => String::from("# config file: none\n"),
+        };
+        for (name, value, source) in lines {
+            result.push_str(&format!("{:<14}= {:<30} # {}\n", name, value, source));
+        }
+        result
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+#[cfg(feature = "embed-inputs")]
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/embedded_inputs.rs"));
+}
+
+// the year of the input files stored directly in the input directory
+const FLAT_LAYOUT_YEAR: u16 = 2018;
+
+///
+/// Are the input files baked into the binary (the `embed-inputs` feature)
+///
+pub fn is_enabled() -> bool {
+    cfg!(feature = "embed-inputs")
+}
+
+///
+/// The input of the puzzle day baked into the binary at build time.
+/// Returns `None` when the input was not available at build time or the `embed-inputs`
+/// feature is off.
+///
+/// # Arguments
+///
+/// * `year` - the puzzle year.
+/// * `day` - the puzzle day.
+///
+#[cfg(feature = "embed-inputs")]
+pub fn embedded_input(year: u16, day: u8) -> Option<&'static str> {
+    generated::INPUTS
+        .iter()
+        .find(|(y, d, _)| *y == year && *d == day)
+        .map(|(_, _, input)| *input)
+}
+
+#[cfg(not(feature = "embed-inputs"))]
+pub fn embedded_input(_year: u16, _day: u8) -> Option<&'static str> {
+    None
+}
+
+///
+/// The embedded input for the input file name. The day comes from the file name,
+/// the year from the parent directory (`input_data/2019/day1_data.txt`), 2018 if it is not a year.
+///
+/// # Arguments
+///
+/// * `file_name` - a path to the file with input data. Example: `./input_data/day1_data.txt`
+///
+pub fn embedded_input_for(file_name: &str) -> Option<&'static str> {
+    let path = Path::new(file_name);
+    let day = path
+        .file_name()?
+        .to_str()?
+        .strip_prefix("day")?
+        .strip_suffix("_data.txt")?
+        .parse::<u8>()
+        .ok()?;
+    let year = path
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.parse::<u16>().ok())
+        .unwrap_or(FLAT_LAYOUT_YEAR);
+    embedded_input(year, day)
+}
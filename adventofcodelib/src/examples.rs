@@ -0,0 +1,267 @@
+use crate::puzzle::{decode_entities, extract_parts, tokenize, Token};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+///
+/// Path to the file with the example input of the day.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+/// * `day` - the puzzle day.
+/// * `index` - the example number, starting from 1.
+///
+pub fn example_file_name(input_root: &str, day: u8, index: usize) -> String {
+    format!(
+        "{}/day{}_example{}.txt",
+        input_root.trim_end_matches('/'),
+        day,
+        index
+    )
+}
+
+///
+/// Path to the file with the expected answers for the examples of the day.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+/// * `day` - the puzzle day.
+///
+pub fn example_answers_file_name(input_root: &str, day: u8) -> String {
+    format!(
+        "{}/day{}_example_answers.txt",
+        input_root.trim_end_matches('/'),
+        day
+    )
+}
+
+///
+/// An example found in the puzzle statement: the example input and the emphasized answer
+/// which follows it (if any).
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleCandidate {
+    pub part: u8,
+    pub input: String,
+    pub answer: Option<String>,
+}
+
+///
+/// A confirmed example: the input file and the answer one of the tasks must give for it.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleFixture {
+    pub day: u8,
+    pub index: usize,
+    pub part: u8,
+    pub answer: String,
+    /// a path to the file with the example input
+    pub file_name: String,
+}
+
+///
+/// Inline code shorter than this is a value in the text, not an example input
+///
+const MIN_INLINE_EXAMPLE_LEN: usize = 8;
+
+///
+/// Find the example inputs and answers in the saved puzzle page.
+/// Example inputs are the `<pre><code>` blocks and the long or comma separated inline `<code>` values,
+/// answers are the emphasized code values (`<code><em>3</em></code>`). Every answer is
+/// attached to the closest example input before it.
+///
+/// Inline comma separated lists (`+1, -2, +3, +1`) are proposed one value per line,
+/// the way the puzzle inputs are written.
+///
+/// # Arguments
+///
+/// * `html` - the saved puzzle page.
+///
+pub fn find_examples(html: &str) -> Vec<ExampleCandidate> {
+    let mut candidates: Vec<ExampleCandidate> = Vec::new();
+    for (i, part_html) in extract_parts(html).iter().enumerate() {
+        let part = (i + 1) as u8;
+        let first_of_part = candidates.len();
+        let mut in_pre = false;
+        let mut in_code = false;
+        let mut in_emphasis = false;
+        let mut code = String::new();
+        let mut answer = String::new();
+        for token in tokenize(part_html) {
+            match token {
+                Token::Open(name, _) => match name.as_str() {
+                    "pre" => in_pre = true,
+                    "code" => {
+                        in_code = true;
+                        code.clear();
+                        answer.clear();
+                    }
+                    "em" => in_emphasis = true,
+                    _ => {}
+                },
+                Token::Close(name) => match name.as_str() {
+                    "pre" => in_pre = false,
+                    "em" => in_emphasis = false,
+                    "code" => {
+                        in_code = false;
+                        if !answer.trim().is_empty() && code.trim() == answer.trim() {
+                            let example = candidates[first_of_part..]
+                                .iter_mut()
+                                .rev()
+                                .find(|c| c.answer.is_none());
+                            if let Some(example) = example {
+                                example.answer = Some(answer.trim().to_string());
+                            }
+                        } else if in_pre {
+                            push_candidate(&mut candidates, part, code.trim_end_matches('\n'));
+                        } else if code.trim().len() >= MIN_INLINE_EXAMPLE_LEN || code.contains(", ")
+                        {
+                            push_candidate(&mut candidates, part, &inline_to_lines(&code));
+                        }
+                    }
+                    _ => {}
+                },
+                Token::Text(text) if in_code => {
+                    let text = decode_entities(text);
+                    if in_emphasis {
+                        answer.push_str(&text);
+                    }
+                    code.push_str(&text);
+                }
+                Token::Text(_) => {}
+            }
+        }
+    }
+    candidates
+}
+
+fn push_candidate(candidates: &mut Vec<ExampleCandidate>, part: u8, input: &str) {
+    let is_known = candidates
+        .iter()
+        .any(|c| c.part == part && c.input == input);
+    if !input.trim().is_empty() && !is_known {
+        candidates.push(ExampleCandidate {
+            part,
+            input: input.to_string(),
+            answer: None,
+        });
+    }
+}
+
+fn inline_to_lines(code: &str) -> String {
+    let code = code.trim();
+    if code.contains(", ") {
+        code.split(',')
+            .map(str::trim)
+            .collect::<Vec<&str>>()
+            .join("\n")
+    } else {
+        code.to_string()
+    }
+}
+
+///
+/// Load the confirmed examples of the day. Returns an empty list if the day has no examples.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+/// * `day` - the puzzle day. The answers file line example: `example1 part1 3`
+///
+pub fn load_fixtures(input_root: &str, day: u8) -> io::Result<Vec<ExampleFixture>> {
+    let file_name = example_answers_file_name(input_root, day);
+    if !Path::new(&file_name).exists() {
+        return Ok(Vec::new());
+    }
+    let mut fixtures = Vec::new();
+    for line in fs::read_to_string(&file_name)?.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Cannot parse string '{}' in '{}'", line, file_name),
+            )
+        };
+        let mut fields = line.splitn(3, ' ');
+        let index = fields
+            .next()
+            .and_then(|f| f.strip_prefix("example"))
+            .and_then(|f| f.parse::<usize>().ok())
+            .ok_or_else(invalid)?;
+        let part = fields
+            .next()
+            .and_then(|f| f.strip_prefix("part"))
+            .and_then(|f| f.parse::<u8>().ok())
+            .ok_or_else(invalid)?;
+        let answer = fields.next().ok_or_else(invalid)?.trim().to_string();
+        fixtures.push(ExampleFixture {
+            day,
+            index,
+            part,
+            answer,
+            file_name: example_file_name(input_root, day, index),
+        });
+    }
+    Ok(fixtures)
+}
+
+///
+/// Store the confirmed example as a fixture of the day. An input already stored for the day
+/// is reused, so the part 1 and part 2 answers of the same example share one input file.
+/// The confirmed answer replaces the answer stored for the same example and part.
+/// Returns the stored fixture.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+/// * `day` - the puzzle day.
+/// * `example` - the confirmed example. It must have an answer.
+///
+pub fn save_fixture(
+    input_root: &str,
+    day: u8,
+    example: &ExampleCandidate,
+) -> io::Result<ExampleFixture> {
+    let answer = example
+        .answer
+        .clone()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the example has no answer"))?;
+    let mut fixtures = load_fixtures(input_root, day)?;
+    let mut index = 1;
+    loop {
+        let file_name = example_file_name(input_root, day, index);
+        if !Path::new(&file_name).exists() {
+            fs::write(&file_name, format!("{}\n", example.input))?;
+            break;
+        }
+        if fs::read_to_string(&file_name)?.trim_end() == example.input.trim_end() {
+            break;
+        }
+        index += 1;
+    }
+    let fixture = ExampleFixture {
+        day,
+        index,
+        part: example.part,
+        answer,
+        file_name: example_file_name(input_root, day, index),
+    };
+    match fixtures
+        .iter_mut()
+        .find(|f| f.index == fixture.index && f.part == fixture.part)
+    {
+        Some(stored) if stored.answer == fixture.answer => return Ok(fixture),
+        Some(stored) => stored.answer = fixture.answer.clone(),
+        None => fixtures.push(fixture.clone()),
+    }
+    let answers = fixtures
+        .iter()
+        .map(|f| format!("example{} part{} {}\n", f.index, f.part, f.answer))
+        .collect::<String>();
+    fs::write(example_answers_file_name(input_root, day), answers)?;
+    Ok(fixture)
+}
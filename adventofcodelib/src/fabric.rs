@@ -0,0 +1,252 @@
+use crate::sweep::{coverage, for_each_region};
+use crate::{cancel, progress, Inch};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+///
+/// The largest bounding box of the claims kept as a grid, the bigger fabric is swept
+/// over the claim edges instead
+///
+pub const DENSE_LIMIT: u64 = 1 << 22;
+
+///
+/// A rectangle of the fabric with the same number of claims over all its square inches
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub xs: Range<u64>,
+    pub ys: Range<u64>,
+    /// the number of claims of every square inch of the region
+    pub count: u32,
+}
+
+impl Region {
+    pub fn area(&self) -> u64 {
+        (self.xs.end - self.xs.start) * (self.ys.end - self.ys.start)
+    }
+
+    ///
+    /// The square inches of the region, row by row
+    ///
+    pub fn squares(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.ys
+            .clone()
+            .flat_map(move |y| self.xs.clone().map(move |x| (x, y)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Cells {
+    /// claim counts of every square of the bounding box, row by row
+    Dense(Vec<u32>),
+    /// the count of the square inches by the number of claims covering them
+    Sparse(BTreeMap<u32, u64>),
+}
+
+///
+/// The fabric of day 3 with the number of claims of every square inch. It covers
+/// the bounding box of the claims only.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fabric {
+    min_x: u64,
+    min_y: u64,
+    width: u64,
+    height: u64,
+    cells: Cells,
+    inches: Vec<Inch>,
+}
+
+impl Fabric {
+    ///
+    /// Lay the claims on the fabric.
+    ///
+    /// # Arguments
+    ///
+    /// * `inches` - the claims.
+    ///
+    pub fn new(inches: &[Inch]) -> Self {
+        let claims = inches.iter().filter(|inch| inch.area() > 0);
+        let min_x = claims.clone().map(|inch| inch.min_x).min().unwrap_or(0);
+        let min_y = claims.clone().map(|inch| inch.min_y).min().unwrap_or(0);
+        let max_x = claims.clone().map(|inch| inch.max_x).max().unwrap_or(0);
+        let max_y = claims.clone().map(|inch| inch.max_y).max().unwrap_or(0);
+        let (width, height) = (max_x - min_x, max_y - min_y);
+        let cells = match width.checked_mul(height) {
+            Some(area) if area <= DENSE_LIMIT => Cells::Dense(vec![0; area as usize]),
+            _ => Cells::Sparse(coverage(inches)),
+        };
+        let mut fabric = Fabric {
+            min_x,
+            min_y,
+            width,
+            height,
+            cells,
+            inches: inches.to_vec(),
+        };
+        if !fabric.is_sparse() {
+            for (i, inch) in inches.iter().enumerate() {
+                progress::report("laying claims", i, inches.len());
+                if cancel::is_cancelled() {
+                    break;
+                }
+                fabric.claim(inch);
+            }
+        }
+        fabric
+    }
+
+    fn claim(&mut self, inch: &Inch) {
+        if let Cells::Dense(cells) = &mut self.cells {
+            for y in inch.min_y..inch.max_y {
+                for x in inch.min_x..inch.max_x {
+                    let i = (y - self.min_y) * self.width + (x - self.min_x);
+                    cells[i as usize] += 1;
+                }
+            }
+        }
+    }
+
+    ///
+    /// The number of claims of the square inch. The sparse fabric checks every claim.
+    ///
+    pub fn claims_at(&self, x: u64, y: u64) -> u32 {
+        match &self.cells {
+            Cells::Dense(cells) => {
+                let inside = (self.min_x..self.min_x + self.width).contains(&x)
+                    && (self.min_y..self.min_y + self.height).contains(&y);
+                if !inside {
+                    return 0;
+                }
+                cells[((y - self.min_y) * self.width + (x - self.min_x)) as usize]
+            }
+            Cells::Sparse(_) => self.inches.iter().filter(|i| i.contains(x, y)).count() as u32,
+        }
+    }
+
+    ///
+    /// The bounding box of the claims: left, top, width and height
+    ///
+    pub fn bounds(&self) -> (u64, u64, u64, u64) {
+        (self.min_x, self.min_y, self.width, self.height)
+    }
+
+    ///
+    /// The claims laid on the fabric
+    ///
+    pub fn claims(&self) -> &[Inch] {
+        &self.inches
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        matches!(self.cells, Cells::Sparse(_))
+    }
+
+    ///
+    /// The claimed regions of the fabric, see `regions_where`
+    ///
+    pub fn claimed_regions(&self) -> Vec<Region> {
+        self.regions_where(|count| count > 0)
+    }
+
+    ///
+    /// The regions with the claim count accepted by `keep`, by their top and left edge.
+    /// The grid gives a region for every run of the same count in a row, the sparse fabric
+    /// gives the regions between the claim edges.
+    ///
+    fn regions_where<F: Fn(u32) -> bool>(&self, keep: F) -> Vec<Region> {
+        let mut regions = Vec::new();
+        match &self.cells {
+            Cells::Dense(cells) => {
+                for (row, counts) in cells.chunks(self.width.max(1) as usize).enumerate() {
+                    let y = self.min_y + row as u64;
+                    let mut start = 0;
+                    while start < counts.len() {
+                        let count = counts[start];
+                        let end = counts[start..]
+                            .iter()
+                            .position(|&c| c != count)
+                            .map_or(counts.len(), |length| start + length);
+                        if keep(count) {
+                            regions.push(Region {
+                                xs: self.min_x + start as u64..self.min_x + end as u64,
+                                ys: y..y + 1,
+                                count,
+                            });
+                        }
+                        start = end;
+                    }
+                }
+            }
+            Cells::Sparse(_) => {
+                for_each_region(&self.inches, |xs, ys, count| {
+                    if keep(count) {
+                        regions.push(Region { xs, ys, count });
+                    }
+                });
+                regions.sort_unstable_by_key(|region| (region.ys.start, region.xs.start));
+            }
+        }
+        regions
+    }
+
+    ///
+    /// The claim counts with the number of square inches having them
+    ///
+    fn counts(&self) -> Box<dyn Iterator<Item = (u32, u64)> + '_> {
+        match &self.cells {
+            Cells::Dense(cells) => Box::new(cells.iter().map(|&count| (count, 1))),
+            Cells::Sparse(areas) => Box::new(areas.iter().map(|(&count, &area)| (count, area))),
+        }
+    }
+
+    ///
+    /// Count of the square inches within exactly `k` claims, `k` is not 0
+    ///
+    pub fn area_exactly(&self, k: u32) -> u64 {
+        self.counts()
+            .filter(|&(count, _)| count == k && k > 0)
+            .map(|(_, area)| area)
+            .sum()
+    }
+
+    ///
+    /// Count of the square inches within `k` or more claims, `k` is not 0
+    ///
+    pub fn area_at_least(&self, k: u32) -> u64 {
+        self.counts()
+            .filter(|&(count, _)| count >= k.max(1))
+            .map(|(_, area)| area)
+            .sum()
+    }
+
+    ///
+    /// Count of the square inches within two or more claims
+    ///
+    pub fn overlap_area(&self) -> u64 {
+        self.area_at_least(2)
+    }
+
+    ///
+    /// The largest number of claims of a square inch and the regions with it, see
+    /// `regions_where`. The depth is 0 without claimed squares.
+    ///
+    pub fn max_depth(&self) -> (u32, Vec<Region>) {
+        let depth = self.counts().map(|(count, _)| count).max().unwrap_or(0);
+        if depth == 0 {
+            return (0, Vec::new());
+        }
+        (depth, self.regions_where(|count| count == depth))
+    }
+
+    ///
+    /// The ids of the claims covering the square inch, in the input order
+    ///
+    pub fn claims_covering(&self, x: u64, y: u64) -> Vec<u32> {
+        self.inches
+            .iter()
+            .filter(|inch| inch.contains(x, y))
+            .map(|inch| inch.id)
+            .collect()
+    }
+}
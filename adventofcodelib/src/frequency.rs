@@ -0,0 +1,139 @@
+use crate::{first_repeat, frequency_sum, get_frequency_changes, FirstRepeat};
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+///
+/// The frequency device of day 1: the list of frequency changes applied over and over
+/// starting from the frequency 0
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrequencyDevice {
+    changes: Vec<i64>,
+}
+
+impl FrequencyDevice {
+    pub fn new(changes: Vec<i64>) -> Self {
+        FrequencyDevice { changes }
+    }
+
+    ///
+    /// Read the frequency changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_name` - a path to the file with input data.
+    ///
+    pub fn load(file_name: &str) -> Self {
+        FrequencyDevice::new(get_frequency_changes(file_name))
+    }
+
+    pub fn changes(&self) -> &[i64] {
+        &self.changes
+    }
+
+    ///
+    /// The frequency change of a whole pass over the list
+    ///
+    pub fn drift(&self) -> i64 {
+        frequency_sum(&self.changes).expect("DAY1: the frequency overflows!")
+    }
+
+    ///
+    /// The resulting frequencies of all the changes, pass after pass. The iterator is
+    /// endless unless the list is empty or the frequency overflows.
+    ///
+    pub fn frequencies(&self) -> Frequencies<'_> {
+        Frequencies {
+            changes: &self.changes,
+            position: 0,
+            frequency: 0,
+        }
+    }
+
+    pub fn first_repeat(&self) -> Option<FirstRepeat> {
+        first_repeat(&self.changes)
+    }
+
+    ///
+    /// The number of complete passes over the list before the first frequency is reached
+    /// twice, `None` when no frequency is ever reached twice.
+    ///
+    pub fn passes_until_repeat(&self) -> Option<u64> {
+        self.first_repeat().map(|repeat| repeat.iteration)
+    }
+
+    ///
+    /// The lowest and the highest frequency reached, the starting one included.
+    /// `None` when a frequency overflows.
+    ///
+    /// # Arguments
+    ///
+    /// * `passes` - the number of passes over the list.
+    ///
+    pub fn range(&self, passes: u64) -> Option<(i64, i64)> {
+        if passes == 0 {
+            return Some((0, 0));
+        }
+        let mut first_pass = (0, 0);
+        let mut count = 0;
+        for frequency in self.frequencies().take(self.changes.len()) {
+            first_pass = (first_pass.0.min(frequency), first_pass.1.max(frequency));
+            count += 1;
+        }
+        if count < self.changes.len() {
+            return None;
+        }
+        // every pass shifts the frequencies of the first one by the drift
+        let shift = i64::try_from(passes - 1)
+            .ok()?
+            .checked_mul(frequency_sum(&self.changes)?)?;
+        Some((
+            first_pass.0.min(first_pass.0.checked_add(shift)?),
+            first_pass.1.max(first_pass.1.checked_add(shift)?),
+        ))
+    }
+
+    ///
+    /// How many times every frequency is reached, the starting one included.
+    /// `None` when the number of changes or a frequency overflows.
+    ///
+    /// # Arguments
+    ///
+    /// * `passes` - the number of passes over the list.
+    ///
+    pub fn histogram(&self, passes: u64) -> Option<BTreeMap<i64, u64>> {
+        let mut histogram = BTreeMap::from([(0, 1)]);
+        let count = (self.changes.len() as u64).checked_mul(passes)?;
+        let count = usize::try_from(count).ok()?;
+        let mut reached = 0;
+        for frequency in self.frequencies().take(count) {
+            *histogram.entry(frequency).or_insert(0) += 1;
+            reached += 1;
+        }
+        if reached < count {
+            return None;
+        }
+        Some(histogram)
+    }
+}
+
+///
+/// The endless iterator over the resulting frequencies of the device
+///
+#[derive(Debug, Clone)]
+pub struct Frequencies<'a> {
+    changes: &'a [i64],
+    position: usize,
+    frequency: i64,
+}
+
+impl Iterator for Frequencies<'_> {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        let change = self.changes.get(self.position)?;
+        self.frequency = self.frequency.checked_add(*change)?;
+        self.position = (self.position + 1) % self.changes.len();
+        Some(self.frequency)
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{cancel, progress};
+use std::collections::HashSet;
+
+///
+/// The number of positions where the ids of the same length differ, `None` for the ids
+/// of different lengths
+///
+pub fn hamming(first: &[char], second: &[char]) -> Option<usize> {
+    if first.len() != second.len() {
+        return None;
+    }
+    Some(first.iter().zip(second).filter(|(a, b)| a != b).count())
+}
+
+///
+/// The least number of inserted, removed or replaced characters turning one id into
+/// the other
+///
+pub fn levenshtein(first: &[char], second: &[char]) -> usize {
+    let mut previous = (0..=second.len()).collect::<Vec<usize>>();
+    let mut current = vec![0; second.len() + 1];
+    for (i, a) in first.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, b) in second.iter().enumerate() {
+            let replace = previous[j] + usize::from(a != b);
+            current[j + 1] = replace.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[second.len()]
+}
+
+///
+/// The distance of the box ids: Hamming for the ids of the same length, Levenshtein
+/// for the others
+///
+pub fn id_distance(first: &[char], second: &[char]) -> usize {
+    hamming(first, second).unwrap_or_else(|| levenshtein(first, second))
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    id: Vec<char>,
+    /// (the Levenshtein distance to the child, the child index)
+    children: Vec<(usize, usize)>,
+}
+
+///
+/// BK-tree of the ids by the Levenshtein distance. The ids within the distance `k` from
+/// the query are searched in the subtrees at the distances `d - k..=d + k` only, where `d`
+/// is the distance from the query to the node.
+///
+#[derive(Debug, Clone, Default)]
+pub struct BkTree {
+    nodes: Vec<Node>,
+}
+
+impl BkTree {
+    pub fn new() -> Self {
+        BkTree::default()
+    }
+
+    ///
+    /// Add the id. Returns `false` when it is already in the tree.
+    ///
+    pub fn insert(&mut self, id: &str) -> bool {
+        let id = id.chars().collect::<Vec<char>>();
+        if self.nodes.is_empty() {
+            self.nodes.push(Node {
+                id,
+                children: Vec::new(),
+            });
+            return true;
+        }
+        let mut node = 0;
+        loop {
+            let distance = levenshtein(&self.nodes[node].id, &id);
+            if distance == 0 {
+                return false;
+            }
+            let child = self.nodes[node]
+                .children
+                .iter()
+                .find(|(d, _)| *d == distance)
+                .map(|&(_, child)| child);
+            match child {
+                Some(child) => node = child,
+                None => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node {
+                        id,
+                        children: Vec::new(),
+                    });
+                    self.nodes[node].children.push((distance, child));
+                    return true;
+                }
+            }
+        }
+    }
+
+    ///
+    /// The ids within the Levenshtein distance `k` from the query with their distances.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - the id to look for.
+    /// * `k` - the largest distance.
+    ///
+    pub fn find(&self, query: &str, k: usize) -> Vec<(usize, String)> {
+        let query = query.chars().collect::<Vec<char>>();
+        let mut found = Vec::new();
+        let mut stack = if self.nodes.is_empty() {
+            Vec::new()
+        } else {
+            vec![0]
+        };
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            let distance = levenshtein(&node.id, &query);
+            if distance <= k {
+                found.push((distance, node.id.iter().collect()));
+            }
+            let range = distance.saturating_sub(k)..=distance + k;
+            stack.extend(
+                node.children
+                    .iter()
+                    .filter(|(d, _)| range.contains(d))
+                    .map(|&(_, child)| child),
+            );
+        }
+        found.sort();
+        found
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+///
+/// Two box ids within the searched distance
+///
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FuzzyMatch {
+    /// the distance of the ids, see `id_distance`
+    pub distance: usize,
+    pub first: String,
+    pub second: String,
+}
+
+///
+/// Find all pairs of the box ids within the distance `k`: the Hamming distance for the ids
+/// of the same length, the Levenshtein distance for the others. The pairs are sorted by
+/// the distance, the repeated ids are ignored.
+///
+/// The Levenshtein distance is never greater than the Hamming one, so the candidates are
+/// found by the BK-tree of the ids seen before and checked by the distance of the pair.
+///
+/// # Arguments
+///
+/// * `ids` - the box ids.
+/// * `k` - the largest distance.
+///
+pub fn fuzzy_pairs(ids: &[String], k: usize) -> Vec<FuzzyMatch> {
+    let mut tree = BkTree::new();
+    let mut unique = HashSet::new();
+    let mut pairs = Vec::new();
+    for (i, id) in ids.iter().enumerate() {
+        progress::report("indexing box ids", i, ids.len());
+        if cancel::is_cancelled() {
+            break;
+        }
+        if !unique.insert(id.as_str()) {
+            continue;
+        }
+        let chars = id.chars().collect::<Vec<char>>();
+        for (_, other) in tree.find(id, k) {
+            let distance = id_distance(&other.chars().collect::<Vec<char>>(), &chars);
+            if distance <= k {
+                let (first, second) = if other < *id {
+                    (other, id.clone())
+                } else {
+                    (id.clone(), other)
+                };
+                pairs.push(FuzzyMatch {
+                    distance,
+                    first,
+                    second,
+                });
+            }
+        }
+        tree.insert(id);
+    }
+    pairs.sort();
+    pairs
+}
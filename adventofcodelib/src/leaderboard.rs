@@ -0,0 +1,507 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::de::{self, Deserializer, Visitor};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::io;
+
+///
+/// A timestamp of the leaderboard export. Older exports write the timestamps as strings.
+///
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Timestamp {
+    Number(i64),
+    Text(String),
+}
+
+impl Timestamp {
+    fn seconds(&self) -> Option<i64> {
+        match self {
+            Timestamp::Number(seconds) => Some(*seconds),
+            Timestamp::Text(seconds) => seconds.parse::<i64>().ok(),
+        }
+    }
+}
+
+///
+/// Read the member id written as a number or, in some exports, as a string.
+///
+fn member_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    struct MemberIdVisitor;
+
+    impl Visitor<'_> for MemberIdVisitor {
+        type Value = i64;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a member id")
+        }
+
+        fn visit_i64<E: de::Error>(self, id: i64) -> Result<i64, E> {
+            Ok(id)
+        }
+
+        fn visit_u64<E: de::Error>(self, id: u64) -> Result<i64, E> {
+            i64::try_from(id).map_err(|_| E::custom(format!("member id {} is too large", id)))
+        }
+
+        fn visit_str<E: de::Error>(self, id: &str) -> Result<i64, E> {
+            id.trim()
+                .parse::<i64>()
+                .map_err(|_| E::custom(format!("cannot parse member id '{}'", id)))
+        }
+    }
+
+    deserializer.deserialize_any(MemberIdVisitor)
+}
+
+#[derive(Deserialize)]
+struct StarJson {
+    get_star_ts: Timestamp,
+}
+
+#[derive(Deserialize)]
+struct MemberJson {
+    #[serde(deserialize_with = "member_id")]
+    id: i64,
+    name: Option<String>,
+    #[serde(default)]
+    local_score: i64,
+    #[serde(default)]
+    completion_day_level: HashMap<String, HashMap<String, StarJson>>,
+}
+
+#[derive(Deserialize)]
+struct LeaderboardJson {
+    event: String,
+    members: HashMap<String, MemberJson>,
+}
+
+///
+/// A member of the private leaderboard with the time of every star the member got
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub id: i64,
+    pub name: String,
+    pub local_score: i64,
+    /// (day, part) -> the time the star was got
+    pub stars: BTreeMap<(u8, u8), DateTime<Utc>>,
+}
+
+///
+/// The private leaderboard. Members are ordered by id.
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leaderboard {
+    pub year: i32,
+    pub members: Vec<Member>,
+}
+
+///
+/// Parse the leaderboard JSON export.
+///
+/// # Arguments
+///
+/// * `json` - the export. Data example:
+///
+/// {"event":"2018","owner_id":1,"members":{"1":{"id":1,"name":"gamak","local_score":10,
+/// "completion_day_level":{"1":{"1":{"get_star_ts":1543641300}}}}}}
+///
+pub fn parse_leaderboard(json: &str) -> Result<Leaderboard, String> {
+    let parsed: LeaderboardJson =
+        serde_json::from_str(json).map_err(|e| format!("Cannot parse leaderboard: {}", e))?;
+    let year = parsed
+        .event
+        .trim()
+        .parse::<i32>()
+        .map_err(|_| format!("Cannot parse event year '{}'", parsed.event))?;
+    let mut members = Vec::new();
+    for member in parsed.members.into_values() {
+        let id = member.id;
+        let mut stars = BTreeMap::new();
+        for (day, parts) in &member.completion_day_level {
+            let day = day
+                .parse::<u8>()
+                .ok()
+                .filter(|day| (1..=25).contains(day))
+                .ok_or_else(|| format!("Cannot parse day '{}' of member {}", day, id))?;
+            for (part, star) in parts {
+                let part = part
+                    .parse::<u8>()
+                    .map_err(|_| format!("Cannot parse part '{}' of member {}", part, id))?;
+                let time = star
+                    .get_star_ts
+                    .seconds()
+                    .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+                    .ok_or_else(|| {
+                        format!("Cannot parse star time of member {} day {}", id, day)
+                    })?;
+                stars.insert((day, part), time);
+            }
+        }
+        members.push(Member {
+            id,
+            name: member
+                .name
+                .unwrap_or_else(|| format!("(anonymous user #{})", id)),
+            local_score: member.local_score,
+            stars,
+        });
+    }
+    members.sort_by_key(|m| m.id);
+    Ok(Leaderboard { year, members })
+}
+
+///
+/// Read and parse the leaderboard JSON export.
+///
+/// # Arguments
+///
+/// * `file_name` - a path to the saved export.
+///
+pub fn load_leaderboard(file_name: &str) -> io::Result<Leaderboard> {
+    let json = fs::read_to_string(file_name)?;
+    parse_leaderboard(&json).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+///
+/// The puzzles unlock at midnight EST (UTC-5).
+///
+/// # Arguments
+///
+/// * `year` - the event year.
+/// * `day` - the puzzle day.
+///
+pub fn unlock_time(year: i32, day: u8) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, 12, u32::from(day), 5, 0, 0)
+        .single()
+        .unwrap_or_else(|| panic!("LEADERBOARD: there is no day {} in {}!", day, year))
+}
+
+///
+/// Time spent by the member on the puzzle of the day
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveTime {
+    pub member: String,
+    pub day: u8,
+    /// from the unlock to the first star
+    pub part1: Option<Duration>,
+    /// from the unlock to the second star
+    pub part2: Option<Duration>,
+    /// from the first star to the second star
+    pub delta: Option<Duration>,
+}
+
+///
+/// Solve times of every member for every day with a star, ordered by day and member.
+///
+pub fn solve_times(leaderboard: &Leaderboard) -> Vec<SolveTime> {
+    let mut result = Vec::new();
+    for day in star_days(leaderboard) {
+        let unlock = unlock_time(leaderboard.year, day);
+        for member in &leaderboard.members {
+            let part1 = member.stars.get(&(day, 1));
+            let part2 = member.stars.get(&(day, 2));
+            if part1.is_none() && part2.is_none() {
+                continue;
+            }
+            result.push(SolveTime {
+                member: member.name.clone(),
+                day,
+                part1: part1.map(|&time| time - unlock),
+                part2: part2.map(|&time| time - unlock),
+                delta: match (part1, part2) {
+                    (Some(&first), Some(&second)) => Some(second - first),
+                    _ => None,
+                },
+            });
+        }
+    }
+    result
+}
+
+fn star_days(leaderboard: &Leaderboard) -> Vec<u8> {
+    let mut days = leaderboard
+        .members
+        .iter()
+        .flat_map(|m| m.stars.keys().map(|&(day, _)| day))
+        .collect::<Vec<u8>>();
+    days.sort_unstable();
+    days.dedup();
+    days
+}
+
+///
+/// Points every member got for every star: the first member to get a star gets as many points
+/// as there are members, the second one gets one point less and so on.
+/// Returns (day, part) -> member id -> points.
+///
+fn star_points(leaderboard: &Leaderboard) -> BTreeMap<(u8, u8), HashMap<i64, i64>> {
+    let members_count = leaderboard.members.len() as i64;
+    let mut times: BTreeMap<(u8, u8), Vec<(DateTime<Utc>, i64)>> = BTreeMap::new();
+    for member in &leaderboard.members {
+        for (&star, &time) in &member.stars {
+            times.entry(star).or_default().push((time, member.id));
+        }
+    }
+    times
+        .into_iter()
+        .map(|(star, mut members)| {
+            members.sort();
+            let points = members
+                .iter()
+                .enumerate()
+                .map(|(rank, &(_, id))| (id, members_count - rank as i64))
+                .collect();
+            (star, points)
+        })
+        .collect()
+}
+
+///
+/// Standing of a member at the end of a day
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub member_id: i64,
+    /// the member name to show
+    pub member: String,
+    pub score: i64,
+    pub rank: usize,
+}
+
+///
+/// The local ranking after every day: the scores include all the stars of the day and of
+/// the days before it. Members with the same score share the rank.
+/// Returns (day, standings ordered by rank).
+///
+pub fn ranking_history(leaderboard: &Leaderboard) -> Vec<(u8, Vec<Standing>)> {
+    let points = star_points(leaderboard);
+    let mut totals: HashMap<i64, i64> = HashMap::new();
+    let mut history = Vec::new();
+    for day in star_days(leaderboard) {
+        for (_, day_points) in points.range((day, 0)..=(day, u8::MAX)) {
+            for (&id, &p) in day_points {
+                *totals.entry(id).or_insert(0) += p;
+            }
+        }
+        let mut standings = leaderboard
+            .members
+            .iter()
+            .map(|member| Standing {
+                member_id: member.id,
+                member: member.name.clone(),
+                score: totals.get(&member.id).cloned().unwrap_or(0),
+                rank: 0,
+            })
+            .collect::<Vec<Standing>>();
+        standings.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.member.cmp(&b.member))
+                .then_with(|| a.member_id.cmp(&b.member_id))
+        });
+        for i in 0..standings.len() {
+            standings[i].rank = if i > 0 && standings[i].score == standings[i - 1].score {
+                standings[i - 1].rank
+            } else {
+                i + 1
+            };
+        }
+        history.push((day, standings));
+    }
+    history
+}
+
+///
+/// Recompute the local score of every member from the star times.
+/// Returns the standings ordered by the score, best first.
+///
+pub fn local_scores(leaderboard: &Leaderboard) -> Vec<Standing> {
+    match ranking_history(leaderboard).pop() {
+        Some((_, standings)) => standings,
+        None => leaderboard
+            .members
+            .iter()
+            .map(|member| Standing {
+                member_id: member.id,
+                member: member.name.clone(),
+                score: 0,
+                rank: 1,
+            })
+            .collect(),
+    }
+}
+
+///
+/// Format a duration as `HH:MM:SS`, durations longer than a day as `Nd HH:MM:SS`.
+///
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds();
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.abs();
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    );
+    if days > 0 {
+        format!("{}{}d {}", sign, days, clock)
+    } else {
+        format!("{}{}", sign, clock)
+    }
+}
+
+///
+/// A table of the leaderboard report
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn new(headers: &[&str]) -> Self {
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    ///
+    /// Render the table for a terminal: columns are aligned, numbers to the right.
+    ///
+    pub fn to_text(&self) -> String {
+        let mut widths = self
+            .headers
+            .iter()
+            .map(|h| h.chars().count())
+            .collect::<Vec<usize>>();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+        let format_row = |row: &[String]| {
+            row.iter()
+                .enumerate()
+                .map(|(i, cell)| {
+                    if i > 0 && cell.starts_with(|c: char| c.is_ascii_digit() || c == '-') {
+                        format!("{:>width$}", cell, width = widths[i])
+                    } else {
+                        format!("{:<width$}", cell, width = widths[i])
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        let mut lines = vec![format_row(&self.headers)];
+        lines.push(
+            widths
+                .iter()
+                .map(|&w| "-".repeat(w))
+                .collect::<Vec<String>>()
+                .join("  "),
+        );
+        lines.extend(self.rows.iter().map(|row| format_row(row)));
+        lines.join("\n") + "\n"
+    }
+
+    ///
+    /// Render the table as CSV.
+    ///
+    pub fn to_csv(&self) -> String {
+        let escape = |cell: &String| {
+            if cell.contains(|c| c == ',' || c == '"' || c == '\n') {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        };
+        let mut result = String::new();
+        for row in std::iter::once(&self.headers).chain(self.rows.iter()) {
+            result.push_str(&row.iter().map(escape).collect::<Vec<String>>().join(","));
+            result.push('\n');
+        }
+        result
+    }
+}
+
+fn format_optional(duration: Option<Duration>) -> String {
+    duration.map(format_duration).unwrap_or_default()
+}
+
+///
+/// Table of the solve times of every member for every day.
+///
+pub fn solve_times_table(leaderboard: &Leaderboard) -> Table {
+    let mut table = Table::new(&["day", "member", "part 1", "part 2", "part 1 to part 2"]);
+    for time in solve_times(leaderboard) {
+        table.rows.push(vec![
+            time.day.to_string(),
+            time.member,
+            format_optional(time.part1),
+            format_optional(time.part2),
+            format_optional(time.delta),
+        ]);
+    }
+    table
+}
+
+///
+/// Table of the recomputed local scores next to the scores of the export.
+///
+pub fn local_scores_table(leaderboard: &Leaderboard) -> Table {
+    let mut table = Table::new(&["rank", "member", "score", "exported score"]);
+    for standing in local_scores(leaderboard) {
+        let exported = leaderboard
+            .members
+            .iter()
+            .find(|m| m.id == standing.member_id)
+            .map(|m| m.local_score)
+            .unwrap_or(0);
+        table.rows.push(vec![
+            standing.rank.to_string(),
+            standing.member,
+            standing.score.to_string(),
+            exported.to_string(),
+        ]);
+    }
+    table
+}
+
+///
+/// Table of the rank of every member after every day.
+///
+pub fn ranking_history_table(leaderboard: &Leaderboard) -> Table {
+    let history = ranking_history(leaderboard);
+    let mut headers = vec![String::from("member")];
+    headers.extend(history.iter().map(|(day, _)| format!("day {}", day)));
+    // (member id, row): members with the same name keep their own rows
+    let mut rows = Vec::new();
+    for member in &leaderboard.members {
+        let mut row = vec![member.name.clone()];
+        for (_, standings) in &history {
+            let standing = standings.iter().find(|s| s.member_id == member.id);
+            row.push(standing.map(|s| s.rank.to_string()).unwrap_or_default());
+        }
+        rows.push((member.id, row));
+    }
+    if let Some((_, last)) = history.last() {
+        let rank_of = |id: i64| last.iter().position(|s| s.member_id == id);
+        rows.sort_by_key(|&(id, _)| rank_of(id));
+    }
+    Table {
+        headers,
+        rows: rows.into_iter().map(|(_, row)| row).collect(),
+    }
+}
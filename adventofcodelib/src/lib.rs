@@ -1,26 +1,125 @@
 use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Lines};
+use std::io::{BufRead, BufReader, Cursor, Lines};
+
+// the derived line parsers refer to the crate by name
+extern crate self as adventofcodelib;
+
+pub mod alloc_stats;
+pub mod cache;
+pub mod cancel;
+pub mod checksum;
+pub mod config;
+pub mod embedded;
+pub mod examples;
+pub mod fabric;
+pub mod frequency;
+pub mod fuzzy;
+pub mod leaderboard;
+pub mod line;
+pub mod progress;
+pub mod puzzle;
+pub mod render;
+pub mod report;
+pub mod runner;
+pub mod solutions;
+pub mod store;
+pub mod sweep;
+
+// the directory with input data
+pub const INPUT_ROOT: &str = "./input_data";
+
+// the path to the files with input data
+pub const FILE_NAME_DAY1: &str = "./input_data/day1_data.txt";
+pub const FILE_NAME_DAY2: &str = "./input_data/day2_data.txt";
+pub const FILE_NAME_DAY3: &str = "./input_data/day3_data.txt";
+pub const FILE_NAME_DAY4: &str = "./input_data/day4_data.txt";
+pub const FILE_NAME_DAY5: &str = "./input_data/day5_data.txt";
+
+///
+/// Path to the file with input data of the day.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data. Example: `./input_data`
+/// * `day` - the puzzle day.
+///
+pub fn input_file_name(input_root: &str, day: u8) -> String {
+    format!("{}/day{}_data.txt", input_root.trim_end_matches('/'), day)
+}
 
 ///
 /// Read file by lines. Return collection of lines.
+/// When the file is missing, its encrypted copy (`<file_name>.enc`) is decrypted with the
+/// input key, then the input baked into the binary by the `embed-inputs` feature is tried.
 ///
 /// # Arguments
 ///
 /// * `file_name` - a path to the file with input data.
 ///
-fn read_input(file_name: &str) -> Lines<BufReader<File>> {
-    BufReader::new(
-        File::open(file_name)
-            .unwrap_or_else(|e| panic!("Cannot read file '{}. The error is {:?}'.", file_name, e)),
-    )
-    .lines()
+fn read_input(file_name: &str) -> Lines<Box<dyn BufRead>> {
+    let reader: Box<dyn BufRead> = match File::open(file_name) {
+        Ok(file) => Box::new(BufReader::new(file)),
+        Err(e) => match store::read_encrypted(file_name) {
+            Ok(Some(input)) => Box::new(Cursor::new(input)),
+            Ok(None) => match embedded::embedded_input_for(file_name) {
+                Some(input) => Box::new(Cursor::new(input)),
+                None => panic!("Cannot read file '{}. The error is {:?}'.", file_name, e),
+            },
+            Err(e) => panic!("Cannot read file '{}. The error is {}'.", file_name, e),
+        },
+    };
+    reader.lines()
+}
+
+///
+/// Read the whole input the way the solutions do: the file, its encrypted copy
+/// or the embedded input.
+///
+/// # Arguments
+///
+/// * `file_name` - a path to the file with input data.
+///
+pub fn input_content(file_name: &str) -> Result<String, String> {
+    match std::fs::read_to_string(file_name) {
+        Ok(content) => Ok(content),
+        Err(e) => match store::read_encrypted(file_name)? {
+            Some(content) => Ok(content),
+            None => embedded::embedded_input_for(file_name)
+                .map(str::to_string)
+                .ok_or_else(|| format!("Cannot read file '{}': {}", file_name, e)),
+        },
+    }
+}
+
+///
+/// Size of the puzzle input
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputStats {
+    pub lines: usize,
+    pub bytes: usize,
+}
+
+///
+/// Count the lines and bytes of the input.
+///
+/// # Arguments
+///
+/// * `file_name` - a path to the file with input data.
+///
+pub fn input_stats(file_name: &str) -> Result<InputStats, String> {
+    let content = input_content(file_name)?;
+    Ok(InputStats {
+        lines: content.lines().count(),
+        bytes: content.len(),
+    })
 }
 
 //-------------------------------------------- day 1 --------------------------------------------//
 
 ///
-/// Transform vector of strings to vector of i32
+/// Read the frequency changes, see `parse_frequency_changes`.
 ///
 /// # Arguments
 ///
@@ -42,14 +141,44 @@ fn read_input(file_name: &str) -> Lines<BufReader<File>> {
 ///
 /// +19
 ///
-fn get_vec_int(file_name: &str) -> Vec<i32> {
-    read_input(file_name)
-        .map(|line| {
-            line.expect("Line reading error!'")
-                .parse::<i32>()
-                .expect("Line parsing error!")
-        })
-        .collect()
+pub(crate) fn get_frequency_changes(file_name: &str) -> Vec<i64> {
+    let input = input_content(file_name).unwrap_or_else(|e| panic!("DAY1: {}", e));
+    parse_frequency_changes(&input).unwrap_or_else(|e| panic!("DAY1: {}", e))
+}
+
+///
+/// Parse the frequency changes separated by new lines or commas. The plus sign is optional,
+/// the whitespace around the changes and the blank lines are skipped.
+///
+/// # Arguments
+///
+/// * `input` - the changes. Example: `+1, -2, +3, +1`
+///
+pub fn parse_frequency_changes(input: &str) -> Result<Vec<i64>, String> {
+    let mut changes = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        for change in line.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            let value = change.parse::<i64>().map_err(|e| {
+                format!(
+                    "Cannot parse string '{}' at line {} as a frequency change: {}",
+                    change,
+                    i + 1,
+                    e
+                )
+            })?;
+            changes.push(value);
+        }
+    }
+    Ok(changes)
+}
+
+///
+/// The resulting frequency of the changes starting from 0, `None` when it overflows.
+///
+pub fn frequency_sum(changes: &[i64]) -> Option<i64> {
+    changes
+        .iter()
+        .try_fold(0i64, |frequency, &change| frequency.checked_add(change))
 }
 
 ///
@@ -99,8 +228,8 @@ fn get_vec_int(file_name: &str) -> Vec<i32> {
 ///
 /// +19
 ///
-pub fn day1_task1(file_name: &str) -> i32 {
-    get_vec_int(file_name).iter().sum()
+pub fn day1_task1(file_name: &str) -> i64 {
+    frequency_sum(&get_frequency_changes(file_name)).expect("DAY1: the frequency overflows!")
 }
 
 ///
@@ -158,16 +287,104 @@ pub fn day1_task1(file_name: &str) -> i32 {
 ///
 /// +19
 ///
-pub fn day1_task2(file_name: &str) -> i32 {
-    let mut current_value = 0i32;
-    let mut history = HashSet::new();
-    for number in get_vec_int(file_name).iter().cycle() {
-        current_value += number;
-        if !history.insert(current_value) {
+/// Returns `None` when no frequency is ever reached twice. Example: `+1, +1`
+///
+pub fn day1_task2(file_name: &str) -> Option<FirstRepeat> {
+    frequency::FrequencyDevice::load(file_name).first_repeat()
+}
+
+///
+/// The first frequency reached twice and when it happens
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FirstRepeat {
+    pub frequency: i64,
+    /// the pass over the list of changes, starting from 0
+    pub iteration: u64,
+    /// the index of the change in the list resulting in the repeated frequency
+    pub position: usize,
+}
+
+///
+/// Find the first frequency reached twice without replaying the changes.
+///
+/// Every pass shifts the frequencies by the drift (the sum of the changes), so the frequency
+/// `g` of the first pass reaches the frequency `f` of the first pass after `(f - g) / drift`
+/// more passes when both have the same residue modulo the drift.
+/// The frequencies are grouped by the residue and sorted, the nearest one in the drift
+/// direction gives the repeat of every frequency, the earliest repeat wins. O(n log n).
+///
+/// # Arguments
+///
+/// * `changes` - the frequency changes. Example: `[1, -2, 3, 1]`
+///
+pub fn first_repeat(changes: &[i64]) -> Option<FirstRepeat> {
+    // the first pass, the starting frequency 0 is already reached
+    let mut seen = HashSet::from([0i64]);
+    let mut frequencies = Vec::with_capacity(changes.len());
+    let mut frequency = 0i64;
+    for (position, &change) in changes.iter().enumerate() {
+        frequency = frequency
+            .checked_add(change)
+            .expect("DAY1: the frequency overflows!");
+        if !seen.insert(frequency) {
+            return Some(FirstRepeat {
+                frequency,
+                iteration: 0,
+                position,
+            });
+        }
+        frequencies.push(frequency);
+    }
+    let drift = frequency;
+    if drift == 0 {
+        // no changes at all, otherwise the last frequency repeats the starting one
+        return None;
+    }
+
+    // (residue, frequency, position), the starting frequency can be reached but it starts
+    // no repeats: its later values are the ones of the last frequency of the first pass
+    let mut groups = frequencies
+        .iter()
+        .enumerate()
+        .map(|(position, &f)| (f.rem_euclid(drift.abs()), f, Some(position)))
+        .chain(std::iter::once((0, 0, None)))
+        .collect::<Vec<(i64, i64, Option<usize>)>>();
+    groups.sort_unstable();
+
+    let mut best: Option<FirstRepeat> = None;
+    for pair in groups.windows(2) {
+        if cancel::is_cancelled() {
             break;
         }
+        let (residue, lower, lower_position) = pair[0];
+        let (next_residue, upper, upper_position) = pair[1];
+        if residue != next_residue {
+            continue;
+        }
+        // a positive drift moves the lower frequency up to the upper one and vice versa
+        let (start, position) = if drift > 0 {
+            (lower, lower_position)
+        } else {
+            (upper, upper_position)
+        };
+        let Some(position) = position else {
+            continue;
+        };
+        let repeat = FirstRepeat {
+            frequency: start + (upper - lower) / drift.abs() * drift,
+            iteration: ((upper - lower) / drift.abs()) as u64,
+            position,
+        };
+        let is_earlier = match best {
+            Some(best) => (repeat.iteration, repeat.position) < (best.iteration, best.position),
+            None => true,
+        };
+        if is_earlier {
+            best = Some(repeat);
+        }
     }
-    current_value
+    best
 }
 
 //-------------------------------------------- day 2 --------------------------------------------//
@@ -203,33 +420,10 @@ pub fn day1_task2(file_name: &str) -> i32 {
 ///
 /// qwugbihrkplymcjoxrsotvdzns
 ///
-pub fn day2_task1(file_name: &str) -> u32 {
-    let mut twice = 0u32;
-    let mut thrice = 0u32;
-    let input = read_input(file_name);
-    for line in input {
-        let l = line.expect("Line reading error!'");
-        let mut is_twice_inserted = false;
-        let mut is_thrice_inserted = false;
-        for c in l.chars() {
-            match l.matches(c).count() {
-                2 => {
-                    if !is_twice_inserted {
-                        is_twice_inserted = true;
-                        twice += 1;
-                    }
-                }
-                3 => {
-                    if !is_thrice_inserted {
-                        is_thrice_inserted = true;
-                        thrice += 1;
-                    }
-                }
-                _ => {}
-            };
-        }
-    }
-    twice * thrice
+pub fn day2_task1(file_name: &str) -> u64 {
+    checksum::Checksum::new(&get_box_ids(file_name), &[2, 3], checksum::Units::Chars)
+        .value()
+        .expect("DAY2: the checksum overflows!")
 }
 
 ///
@@ -269,12 +463,43 @@ pub fn day2_task1(file_name: &str) -> u32 {
 /// qwugbihrkplymcjoxrsotvdzns
 ///
 pub fn day2_task2(file_name: &str) -> String {
-    let input = read_input(file_name)
-        .map(|s| s.expect("Line reading error!'"))
+    let (line1, line2) = most_similar_pair(file_name);
+    common_letters(&line1, &line2)
+}
+
+///
+/// Explain the day 2 second task answer: the two box ids and where they differ.
+///
+pub fn day2_task2_explain(file_name: &str) -> String {
+    let (line1, line2) = most_similar_pair(file_name);
+    let differences = line1
+        .chars()
+        .zip(line2.chars())
+        .enumerate()
+        .filter(|(_, (char1, char2))| char1 != char2)
+        .map(|(i, (char1, char2))| format!("position {} ({} vs {})", i + 1, char1, char2))
         .collect::<Vec<String>>();
+    format!(
+        "box ids {} and {} differ at {} → common letters {}",
+        line1,
+        line2,
+        differences.join(", "),
+        common_letters(&line1, &line2)
+    )
+}
+
+///
+/// Find two box ids with the most letters equal at the same positions
+///
+fn most_similar_pair(file_name: &str) -> (String, String) {
+    let input = get_box_ids(file_name);
     let mut max_similar_count = 0u8;
     let mut similar_pair = (String::new(), String::new());
-    for line1 in input.clone() {
+    for (i, line1) in input.clone().into_iter().enumerate() {
+        progress::report("comparing box ids", i, input.len());
+        if cancel::is_cancelled() {
+            break;
+        }
         for line2 in input.clone() {
             if line1.eq(&line2) {
                 continue;
@@ -291,8 +516,12 @@ pub fn day2_task2(file_name: &str) -> String {
             }
         }
     }
+    similar_pair
+}
+
+fn common_letters(line1: &str, line2: &str) -> String {
     let mut result = String::new();
-    for (char1, char2) in similar_pair.0.chars().zip(similar_pair.1.chars()) {
+    for (char1, char2) in line1.chars().zip(line2.chars()) {
         if char1.eq(&char2) {
             result.push(char1);
         }
@@ -300,18 +529,107 @@ pub fn day2_task2(file_name: &str) -> String {
     result
 }
 
-//-------------------------------------------- day 3 --------------------------------------------//
-#[macro_use]
-extern crate lazy_static;
-extern crate regex;
-use regex::Regex;
+fn get_box_ids(file_name: &str) -> Vec<String> {
+    read_input(file_name)
+        .map(|s| s.expect("Line reading error!'"))
+        .collect()
+}
 
-const RECT_SIZE: usize = 1000;
+///
+/// The day 2 second task without comparing every pair of the box ids.
+///
+pub fn day2_task2_fast(file_name: &str) -> String {
+    find_near_duplicate(&get_box_ids(file_name))
+        .unwrap_or_else(|e| panic!("DAY2: {}", e))
+        .common
+}
 
-lazy_static! {
-    static ref RE_DAY3: Regex =
-        Regex::new(r"^#(?P<id>\d+)\s@\s(?P<left>\d+),(?P<top>\d+):\s*(?P<wide>\d+)x(?P<toll>\d+)$")
-            .unwrap();
+///
+/// Two box ids differing by exactly one character
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NearDuplicate {
+    pub first: String,
+    pub second: String,
+    /// the index of the differing character, starting from 0
+    pub position: usize,
+    /// the letters of the ids without the differing one
+    pub common: String,
+}
+
+///
+/// Find the only pair of box ids differing by exactly one character. For every position the
+/// ids are bucketed by their text with the character at the position left out, the ids
+/// of a bucket differ at that position only. O(n·L²) for n ids of length L.
+/// Fails when no pair or several pairs differ by exactly one character.
+///
+/// # Arguments
+///
+/// * `ids` - the box ids. Example: `["abcde", "fghij", "klmno", "pqrst", "fguij"]`
+///
+pub fn find_near_duplicate(ids: &[String]) -> Result<NearDuplicate, String> {
+    let mut unique = HashSet::new();
+    let ids = ids
+        .iter()
+        .filter(|id| unique.insert(id.as_str()))
+        .map(|id| id.chars().collect::<Vec<char>>())
+        .collect::<Vec<Vec<char>>>();
+    let length = ids.iter().map(Vec::len).max().unwrap_or(0);
+    let mut pairs: Vec<NearDuplicate> = Vec::new();
+    for position in 0..length {
+        progress::report("bucketing box ids", position, length);
+        let mut buckets: HashMap<_, Vec<&[char]>> = HashMap::new();
+        for id in ids.iter().filter(|id| position < id.len()) {
+            let masked = (&id[..position], &id[position + 1..]);
+            buckets.entry(masked).or_default().push(id.as_slice());
+        }
+        for ((before, after), bucket) in buckets {
+            for (i, first) in bucket.iter().enumerate() {
+                for second in &bucket[i + 1..] {
+                    pairs.push(NearDuplicate {
+                        first: first.iter().collect(),
+                        second: second.iter().collect(),
+                        position,
+                        common: before.iter().chain(after).collect(),
+                    });
+                }
+            }
+        }
+        if pairs.len() > 1 {
+            break;
+        }
+    }
+    pairs.sort_by(|a, b| (&a.first, &a.second).cmp(&(&b.first, &b.second)));
+    match pairs.len() {
+        0 => Err(String::from(
+            "There are no box ids differing by exactly one character",
+        )),
+        1 => Ok(pairs.remove(0)),
+        _ => Err(format!(
+            "Several box ids differ by exactly one character: {}",
+            pairs
+                .iter()
+                .map(|pair| format!("{} and {}", pair.first, pair.second))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )),
+    }
+}
+
+//-------------------------------------------- day 3 --------------------------------------------//
+use line::LinePattern;
+
+///
+/// The claim line as it is written in the input: "#1318 @ 428,284: 25x21"
+///
+#[derive(LinePattern)]
+#[line_pattern("#{id} @ {left},{top}: {wide}x{toll}")]
+struct InchLine {
+    id: u32,
+    left: u32,
+    top: u32,
+    wide: u32,
+    toll: u32,
 }
 
 ///
@@ -320,20 +638,21 @@ lazy_static! {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Inch {
     id: u32,
-    min_x: u32,
-    max_x: u32,
-    min_y: u32,
-    max_y: u32,
+    min_x: u64,
+    // the edges are past the claimed squares and may be past u32::MAX
+    max_x: u64,
+    min_y: u64,
+    max_y: u64,
 }
 
 impl Inch {
     pub fn new(id: u32, left: u32, top: u32, wide: u32, toll: u32) -> Self {
         Inch {
             id,
-            min_x: left,
-            max_x: left + wide,
-            min_y: top,
-            max_y: top + toll,
+            min_x: u64::from(left),
+            max_x: u64::from(left) + u64::from(wide),
+            min_y: u64::from(top),
+            max_y: u64::from(top) + u64::from(toll),
         }
     }
 
@@ -341,14 +660,32 @@ impl Inch {
     /// calculates overlap of two inches. returns boll value - does inches overlap or not.
     /// returns coordinates of the overlap rect
     ///
-    fn get_overlap(&self, other: Inch) -> (bool, u32, u32, u32, u32) {
+    fn get_overlap(&self, other: Inch) -> (bool, u64, u64, u64, u64) {
         let max_min_x = self.min_x.max(other.min_x);
         let min_max_x = self.max_x.min(other.max_x);
-        let x = min_max_x as i32 - max_min_x as i32;
         let max_min_y = self.min_y.max(other.min_y);
         let min_max_y = self.max_y.min(other.max_y);
-        let y = min_max_y as i32 - max_min_y as i32;
-        (x > 0 && y > 0, max_min_x, min_max_x, max_min_y, min_max_y)
+        (
+            max_min_x < min_max_x && max_min_y < min_max_y,
+            max_min_x,
+            min_max_x,
+            max_min_y,
+            min_max_y,
+        )
+    }
+
+    ///
+    /// Count of the claimed square inches
+    ///
+    pub fn area(&self) -> u64 {
+        (self.max_x - self.min_x) * (self.max_y - self.min_y)
+    }
+
+    ///
+    /// Is the square inch within the claim
+    ///
+    pub fn contains(&self, x: u64, y: u64) -> bool {
+        (self.min_x..self.max_x).contains(&x) && (self.min_y..self.max_y).contains(&y)
     }
 }
 
@@ -360,32 +697,11 @@ impl Inch {
 /// * `input` - a string for a parsing. Input data example: "#1318 @ 428,284: 25x21"
 ///
 pub fn parse_inch(input: &str) -> Inch {
-    let mut id = 0u32;
-    let mut left = 0u32;
-    let mut top = 0u32;
-    let mut wide = 0u32;
-    let mut toll = 0u32;
-    if !RE_DAY3.is_match(input) {
-        panic!("Cannot parse string '{}'", input);
-    }
-    for cap in RE_DAY3.captures_iter(input) {
-        id = cap["id"]
-            .parse::<u32>()
-            .expect("DAY3: Cannot parse id value!");
-        left = cap["left"]
-            .parse::<u32>()
-            .expect("DAY3: Cannot parse left value!");
-        top = cap["top"]
-            .parse::<u32>()
-            .expect("DAY3: Cannot parse top value!");
-        wide = cap["wide"]
-            .parse::<u32>()
-            .expect("DAY3: Cannot parse wide value!");
-        toll = cap["toll"]
-            .parse::<u32>()
-            .expect("DAY3: Cannot parse toll value!");
-    }
-    Inch::new(id, left, top, wide, toll)
+    let line = input.parse::<InchLine>().unwrap_or_else(|e| match e.field {
+        Some(field) => panic!("DAY3: Cannot parse {} value! {}", field, e),
+        None => panic!("{}", e),
+    });
+    Inch::new(line.id, line.left, line.top, line.wide, line.toll)
 }
 
 ///
@@ -401,31 +717,12 @@ pub fn parse_inch(input: &str) -> Inch {
 ///
 /// #3 @ 734,527: 23x10
 ///
-fn parse_inches(file_name: &str) -> Vec<Inch> {
+pub fn parse_inches(file_name: &str) -> Vec<Inch> {
     read_input(file_name)
         .map(|s| parse_inch(&s.expect("Line reading error!'")))
         .collect::<Vec<Inch>>()
 }
 
-///
-/// Get count of true values in a vector of vectors
-///
-/// # Arguments
-///
-/// * `input` - a vector of vectors. Contains bool types only
-///
-fn collect_squares_count(input: &[[bool; RECT_SIZE]; RECT_SIZE]) -> u32 {
-    let mut true_count = 0u32;
-    for i in input.iter() {
-        for &value in i.iter() {
-            if value {
-                true_count += 1;
-            }
-        }
-    }
-    true_count
-}
-
 ///
 /// # The task explanation
 /// The problem is that many of the claims overlap, causing two or more claims to cover part of
@@ -471,25 +768,8 @@ fn collect_squares_count(input: &[[bool; RECT_SIZE]; RECT_SIZE]) -> u32 {
 ///
 /// #3 @ 734,527: 23x10
 ///
-pub fn day3_task1(file_name: &str) -> u32 {
-    let mut result = [[false; RECT_SIZE]; RECT_SIZE];
-    let inches = parse_inches(file_name);
-    for inch1 in inches.clone() {
-        for inch2 in inches.clone() {
-            if inch1 == inch2 {
-                continue;
-            }
-            let (is_overlap, max_min_x, min_max_x, max_min_y, min_max_y) = inch1.get_overlap(inch2);
-            if is_overlap {
-                for x in max_min_x..min_max_x {
-                    for y in max_min_y..min_max_y {
-                        result[x as usize][y as usize] = true;
-                    }
-                }
-            }
-        }
-    }
-    collect_squares_count(&result)
+pub fn day3_task1(file_name: &str) -> u64 {
+    fabric::Fabric::new(&parse_inches(file_name)).overlap_area()
 }
 
 ///
@@ -559,6 +839,10 @@ pub fn day3_task2(file_name: &str) -> u32 {
         list_of_inches.push(inch_claimed);
     }
     for i in 0..list_of_inches.len() {
+        progress::report("comparing claims", i, list_of_inches.len());
+        if cancel::is_cancelled() {
+            break;
+        }
         for y in 0..list_of_inches.len() {
             if list_of_inches[i].inch == list_of_inches[y].inch {
                 continue;
@@ -581,30 +865,47 @@ pub fn day3_task2(file_name: &str) -> u32 {
     id
 }
 
+///
+/// The day 3 first task by the sweep line, see `sweep::overlap_area`.
+///
+pub fn day3_task1_sweep(file_name: &str) -> u64 {
+    sweep::overlap_area(&parse_inches(file_name))
+}
+
+///
+/// The day 3 second task by the sweep line. Panics unless exactly one claim is intact.
+///
+pub fn day3_task2_sweep(file_name: &str) -> u32 {
+    match sweep::intact_claims(&parse_inches(file_name))[..] {
+        [id] => id,
+        [] => panic!("DAY3: no claim is intact!"),
+        ref ids => panic!("DAY3: several claims are intact: {:?}!", ids),
+    }
+}
+
 //-------------------------------------------- day 4 --------------------------------------------//
-//#[macro_use]
-//extern crate lazy_static;
-//extern crate regex;
-//use regex::Regex;
 extern crate chrono;
 
 use chrono::{DateTime, FixedOffset, Timelike};
 
-lazy_static! {
-    static ref RE_DAY4: Regex =
-        Regex::new(r"^\[(?P<year>\d+)-(?P<month>\d+)-(?P<day>\d+)\s+(?P<hour>\d+):(?P<minute>\d+)\]\s+(?P<action>.*)")
-            .unwrap();
+///
+/// The record line as it is written in the input: "[1518-11-01 00:00] Guard #10 begins shift"
+///
+#[derive(LinePattern)]
+#[line_pattern("[{year}-{month}-{day} {hour}:{minute}] {action}")]
+struct MessageLine {
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    action: String,
 }
 
 const MINUTE_BOOL: [bool; 60] = [false; 60];
 
 #[derive(Debug)]
 struct Message {
-    year: u16,
-    month: u8,
-    day: u8,
-    hour: u8,
-    minute: u8,
     action: String,
     date_time: DateTime<FixedOffset>,
 }
@@ -622,15 +923,7 @@ impl Message {
         );
         let date_time = DateTime::parse_from_str(&date, "%Y-%m-%d %H:%M %z")
             .unwrap_or_else(|_| panic!("DAY4: Cannot parse date '{}'!", &date));
-        Message {
-            year,
-            month,
-            day,
-            hour,
-            minute,
-            action,
-            date_time,
-        }
+        Message { action, date_time }
     }
 }
 
@@ -638,8 +931,6 @@ impl Message {
 struct Guard {
     id: u16,
     sleep_duration: u16,
-    start_sleep: u16,
-    stop_sleep: u16,
     sleep_period: Vec<bool>,
 }
 
@@ -656,14 +947,38 @@ impl Guard {
         Guard {
             id,
             sleep_duration,
-            start_sleep: start_sleep as u16,
-            stop_sleep: (stop_sleep - 1u32) as u16,
             sleep_period: sleep_period.to_vec(),
         }
     }
 }
 
 pub fn day4_task1(file_name: &str) -> u32 {
+    let (id, _, minute, _) = sleepiest_guard(file_name);
+    u32::from(id) * (minute as u32)
+}
+
+///
+/// Explain the day 4 first task answer: the guard who slept most and his sleepiest minute.
+///
+pub fn day4_task1_explain(file_name: &str) -> String {
+    let (id, total, minute, times) = sleepiest_guard(file_name);
+    format!(
+        "guard #{} slept {} minutes total; most frequent minute {} ({} times) → {}×{} = {}",
+        id,
+        total,
+        minute,
+        times,
+        minute,
+        id,
+        u32::from(id) * (minute as u32)
+    )
+}
+
+///
+/// Find the guard who slept most. Returns his id, the total sleep minutes, the minute
+/// he slept most often and how many times he slept at it.
+///
+fn sleepiest_guard(file_name: &str) -> (u16, u16, usize, u32) {
     let mut messages = get_messages(file_name);
     messages.sort_by(|a, b| a.date_time.partial_cmp(&b.date_time).unwrap());
     let guards = get_guards_list(&messages);
@@ -677,7 +992,7 @@ pub fn day4_task1(file_name: &str) -> u32 {
             result.insert(guard.id, guard.sleep_duration);
         }
     }
-    let (key, _) = result
+    let (key, total) = result
         .iter()
         .max_by(|&(_, a), &(_, b)| a.partial_cmp(b).unwrap())
         .unwrap();
@@ -693,10 +1008,35 @@ pub fn day4_task1(file_name: &str) -> u32 {
     }
     let max_value = minute.iter().max().unwrap();
     let position = minute.iter().position(|el| el.eq(max_value)).unwrap();
-    u32::from(*key) * (position as u32)
+    (*key, *total, position, *max_value)
 }
 
 pub fn day4_task2(file_name: &str) -> u32 {
+    let (id, minute, _) = most_regular_guard(file_name);
+    u32::from(id) * (minute as u32)
+}
+
+///
+/// Explain the day 4 second task answer: the guard most frequently asleep on the same minute.
+///
+pub fn day4_task2_explain(file_name: &str) -> String {
+    let (id, minute, times) = most_regular_guard(file_name);
+    format!(
+        "guard #{} slept at minute {} more often than any guard at any minute ({} times) → {}×{} = {}",
+        id,
+        minute,
+        times,
+        minute,
+        id,
+        u32::from(id) * (minute as u32)
+    )
+}
+
+///
+/// Find the guard most frequently asleep on the same minute. Returns his id, the minute
+/// and how many times he slept at it.
+///
+fn most_regular_guard(file_name: &str) -> (u16, usize, u16) {
     let mut messages = get_messages(file_name);
     messages.sort_by(|a, b| a.date_time.partial_cmp(&b.date_time).unwrap());
     let guards = get_guards_list(&messages);
@@ -712,7 +1052,7 @@ pub fn day4_task2(file_name: &str) -> u32 {
             current_max = max_value;
         }
     }
-    u32::from(current_id) * (current_minute as u32)
+    (current_id, current_minute, current_max)
 }
 
 fn get_guards_minutes(guards: &[Guard]) -> HashMap<u16, Vec<u16>> {
@@ -789,36 +1129,20 @@ fn get_messages(file_name: &str) -> Vec<Message> {
 }
 
 fn parse_message(input: &str) -> Message {
-    let mut year: u16 = 0;
-    let mut month: u8 = 0;
-    let mut day: u8 = 0;
-    let mut hour: u8 = 0;
-    let mut minute: u8 = 0;
-    let mut action: String = String::new();
-    if !RE_DAY4.is_match(input) {
-        panic!("Cannot parse string '{}'", input);
-    }
-    for cap in RE_DAY4.captures_iter(input) {
-        year = cap["year"]
-            .parse::<u16>()
-            .expect("DAY4: Cannot parse year value!");
-        month = cap["month"]
-            .parse::<u8>()
-            .expect("DAY4: Cannot parse month value!");
-        day = cap["day"]
-            .parse::<u8>()
-            .expect("DAY4: Cannot parse day value!");
-        hour = cap["hour"]
-            .parse::<u8>()
-            .expect("DAY4: Cannot parse hours value!");
-        minute = cap["minute"]
-            .parse::<u8>()
-            .expect("DAY4: Cannot parse minute value!");
-        action = cap["action"]
-            .parse::<String>()
-            .expect("DAY4: Cannot parse action value!");
-    }
-    Message::new(year, month, day, hour, minute, action)
+    let line = input
+        .parse::<MessageLine>()
+        .unwrap_or_else(|e| match e.field {
+            Some(field) => panic!("DAY4: Cannot parse {} value! {}", field, e),
+            None => panic!("{}", e),
+        });
+    Message::new(
+        line.year,
+        line.month,
+        line.day,
+        line.hour,
+        line.minute,
+        line.action,
+    )
 }
 
 //-------------------------------------------- day 5 --------------------------------------------//
@@ -829,10 +1153,37 @@ pub fn day5_task1(file_name: &str) -> usize {
 }
 
 pub fn day5_task2(file_name: &str) -> usize {
+    let (_, len) = best_removal(&get_word(file_name));
+    len
+}
+
+///
+/// Explain the day 5 second task answer: which unit removal gives the shortest polymer.
+///
+pub fn day5_task2_explain(file_name: &str) -> String {
     let word = get_word(file_name);
+    let (letter, len) = best_removal(&word);
+    format!(
+        "removing units {}/{} leaves the shortest polymer: {} units ({} without removing any unit)",
+        letter,
+        letter.to_ascii_uppercase(),
+        len,
+        remove_double(word.as_bytes())
+    )
+}
+
+///
+/// Find the unit whose removal gives the shortest fully reacted polymer.
+/// Returns the unit letter and the polymer length.
+///
+fn best_removal(word: &str) -> (char, usize) {
     let alphabet = "abcdefghijklmnopqrstuvwxyz".chars();
-    let mut result = std::usize::MAX;
-    for letter in alphabet {
+    let mut result = (' ', std::usize::MAX);
+    for (i, letter) in alphabet.enumerate() {
+        progress::report("reacting polymers", i, 26);
+        if cancel::is_cancelled() {
+            break;
+        }
         let upper = letter.to_ascii_uppercase();
         let bytes: Vec<u8> = word
             .chars()
@@ -840,14 +1191,78 @@ pub fn day5_task2(file_name: &str) -> usize {
             .map(|x| x as u8)
             .collect();
         let len = remove_double(bytes.as_slice());
-        if len < result {
-            result = len;
+        if len < result.1 {
+            result = (letter, len);
+        }
+    }
+    result
+}
+
+///
+/// The day 5 second task with a single prepass: the whole polymer is reacted once, every
+/// unit removal starts from the reacted polymer, which is much shorter.
+///
+pub fn day5_task2_fast(file_name: &str) -> usize {
+    let reacted = react(get_word(file_name).as_bytes());
+    let mut result = usize::MAX;
+    for (i, letter) in (b'a'..=b'z').enumerate() {
+        progress::report("reacting polymers", i, 26);
+        if cancel::is_cancelled() {
+            break;
         }
+        result = result.min(react_without(&reacted, letter).len());
     }
     result
 }
 
+///
+/// The single prepass day 5 second task trying the unit removals on all cores.
+///
+pub fn day5_task2_parallel(file_name: &str) -> usize {
+    let reacted = react(get_word(file_name).as_bytes());
+    let letters = (b'a'..=b'z').collect::<Vec<u8>>();
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let reacted = &reacted;
+    std::thread::scope(|scope| {
+        letters
+            .chunks(letters.len().div_ceil(threads))
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|&letter| react_without(reacted, letter).len())
+                        .min()
+                        .unwrap_or(usize::MAX)
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().expect("DAY5: a worker thread panicked!"))
+            .min()
+            .unwrap_or(usize::MAX)
+    })
+}
+
+///
+/// React the polymer without the units of the letter (both polarities).
+///
+fn react_without(polymer: &[u8], letter: u8) -> Vec<u8> {
+    let polymer = polymer
+        .iter()
+        .copied()
+        .filter(|unit| unit.to_ascii_lowercase() != letter)
+        .collect::<Vec<u8>>();
+    react(&polymer)
+}
+
 fn remove_double(bytes: &[u8]) -> usize {
+    react(bytes).len()
+}
+
+///
+/// Fully react the polymer. Returns the units left.
+///
+fn react(bytes: &[u8]) -> Vec<u8> {
     let mut result: Vec<u8> = Vec::new();
     for &byte in bytes {
         let must_add = match result.last() {
@@ -863,7 +1278,7 @@ fn remove_double(bytes: &[u8]) -> usize {
             result.pop();
         }
     }
-    result.len()
+    result
 }
 
 fn get_word(file_name: &str) -> String {
@@ -0,0 +1,155 @@
+use std::fmt;
+use std::str::FromStr;
+
+pub use adventofcodelib_derive::LinePattern;
+
+///
+/// A piece of a line pattern made by `#[derive(LinePattern)]`
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Piece {
+    /// text which must be in the line, a space matches zero or more whitespace characters
+    Literal(&'static str),
+    /// the name of the field taking the text up to the next literal
+    Field(&'static str),
+}
+
+///
+/// The line does not match the pattern or a field value cannot be parsed
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineError {
+    pub input: String,
+    /// the field which cannot be parsed, `None` when the line does not match the pattern
+    pub field: Option<&'static str>,
+    /// the column of the error, starting from 1
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.field {
+            Some(field) => write!(
+                f,
+                "Cannot parse string '{}': invalid {} value at column {}: {}",
+                self.input, field, self.column, self.message
+            ),
+            None => write!(
+                f,
+                "Cannot parse string '{}': {} at column {}",
+                self.input, self.message, self.column
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LineError {}
+
+fn column(input: &str, position: usize) -> usize {
+    input[..position].chars().count() + 1
+}
+
+///
+/// Match the literal at the position. Returns the position after it.
+/// A whitespace run of the literal matches zero or more whitespace characters.
+///
+fn match_literal(input: &str, position: usize, literal: &str) -> Option<usize> {
+    let mut rest = &input[position..];
+    for c in literal.chars() {
+        if c.is_whitespace() {
+            rest = rest.trim_start();
+        } else {
+            rest = rest.strip_prefix(c)?;
+        }
+    }
+    Some(input.len() - rest.len())
+}
+
+///
+/// Does the field end at the position before the literal. A literal of whitespace only
+/// matches anywhere, so it ends the field at a whitespace character only.
+///
+fn ends_field(input: &str, position: usize, literal: &str) -> bool {
+    let is_blank = literal.chars().all(char::is_whitespace);
+    match_literal(input, position, literal).is_some()
+        && (!is_blank || input[position..].starts_with(char::is_whitespace))
+}
+
+///
+/// Split the line into the field values by the pattern. Returns the text and the position
+/// of every field in the pattern order.
+///
+/// # Arguments
+///
+/// * `input` - the line. Example: `#1318 @ 428,284: 25x21`
+/// * `pieces` - the pattern.
+///
+pub fn match_pattern<'a>(
+    input: &'a str,
+    pieces: &[Piece],
+) -> Result<Vec<(&'a str, usize)>, LineError> {
+    let error = |position: usize, message: String| LineError {
+        input: input.to_string(),
+        field: None,
+        column: column(input, position),
+        message,
+    };
+    let mut values = Vec::new();
+    let mut position = 0;
+    for (i, piece) in pieces.iter().enumerate() {
+        match *piece {
+            Piece::Literal(literal) => {
+                position = match_literal(input, position, literal)
+                    .ok_or_else(|| error(position, format!("expected '{}'", literal)))?;
+            }
+            Piece::Field(field) => {
+                let end = match pieces.get(i + 1) {
+                    Some(Piece::Literal(next)) => input[position..]
+                        .char_indices()
+                        .skip(1)
+                        .map(|(offset, _)| position + offset)
+                        .find(|&end| ends_field(input, end, next))
+                        .ok_or_else(|| {
+                            error(position, format!("expected {} and '{}'", field, next))
+                        })?,
+                    _ if position < input.len() => input.len(),
+                    _ => return Err(error(position, format!("expected {}", field))),
+                };
+                values.push((&input[position..end], position));
+                position = end;
+            }
+        }
+    }
+    if position != input.len() {
+        return Err(error(position, String::from("unexpected text")));
+    }
+    Ok(values)
+}
+
+///
+/// Parse the field value matched by `match_pattern`.
+///
+/// # Arguments
+///
+/// * `input` - the whole line.
+/// * `field` - the field name.
+/// * `value` - the text and the position of the field value.
+///
+pub fn parse_field<T>(
+    input: &str,
+    field: &'static str,
+    value: (&str, usize),
+) -> Result<T, LineError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let (text, position) = value;
+    text.parse::<T>().map_err(|e| LineError {
+        input: input.to_string(),
+        field: Some(field),
+        column: column(input, position),
+        message: format!("{} ('{}')", e, text),
+    })
+}
@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+
+///
+/// Receives the progress of a long running solution
+///
+pub trait ProgressReporter: Send + Sync {
+    ///
+    /// # Arguments
+    ///
+    /// * `phase` - what the solution is doing. Example: `reacting polymers`
+    /// * `done` - how many steps of the phase are done.
+    /// * `total` - how many steps the phase has.
+    ///
+    fn report(&self, phase: &str, done: usize, total: usize);
+}
+
+impl<F: Fn(&str, usize, usize) + Send + Sync> ProgressReporter for F {
+    fn report(&self, phase: &str, done: usize, total: usize) {
+        self(phase, done, total)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<dyn ProgressReporter>>> = const { RefCell::new(None) };
+}
+
+///
+/// Run the function with the reporter installed for the current thread, so the solutions
+/// called by the function report their progress to it.
+///
+/// # Arguments
+///
+/// * `reporter` - the reporter to install.
+/// * `f` - the function to run.
+///
+pub fn with_reporter<T, F: FnOnce() -> T>(reporter: Arc<dyn ProgressReporter>, f: F) -> T {
+    let previous = CURRENT.with(|current| current.replace(Some(reporter)));
+    let result = f();
+    CURRENT.with(|current| *current.borrow_mut() = previous);
+    result
+}
+
+///
+/// Report the progress of the current solution. Does nothing when no reporter is installed.
+///
+/// # Arguments
+///
+/// * `phase` - what the solution is doing.
+/// * `done` - how many steps of the phase are done.
+/// * `total` - how many steps the phase has.
+///
+pub fn report(phase: &str, done: usize, total: usize) {
+    CURRENT.with(|current| {
+        if let Some(reporter) = current.borrow().as_ref() {
+            reporter.report(phase, done, total);
+        }
+    });
+}
@@ -0,0 +1,611 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+///
+/// Path to the saved puzzle page of the day.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+/// * `day` - the puzzle day.
+///
+pub fn puzzle_html_file_name(input_root: &str, day: u8) -> String {
+    format!(
+        "{}/day{}_puzzle.html",
+        input_root.trim_end_matches('/'),
+        day
+    )
+}
+
+///
+/// Path to the Markdown version of the puzzle statement of the day.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+/// * `day` - the puzzle day.
+///
+pub fn puzzle_markdown_file_name(input_root: &str, day: u8) -> String {
+    format!("{}/day{}_puzzle.md", input_root.trim_end_matches('/'), day)
+}
+
+///
+/// A tag or a piece of text of the html page
+///
+#[derive(Debug, PartialEq)]
+pub(crate) enum Token<'a> {
+    Open(String, &'a str),
+    Close(String),
+    Text(&'a str),
+}
+
+///
+/// Split html into tags and text. Comments, doctype and self closing tags are
+/// returned as open tags and must be ignored by the caller.
+///
+pub(crate) fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            rest = match rest.find("-->") {
+                Some(end) => &rest[end + 3..],
+                None => "",
+            };
+            continue;
+        }
+        if rest.starts_with('<') {
+            let end = match rest.find('>') {
+                Some(end) => end,
+                None => {
+                    tokens.push(Token::Text(rest));
+                    break;
+                }
+            };
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            if let Some(name) = tag.strip_prefix('/') {
+                tokens.push(Token::Close(name.trim().to_ascii_lowercase()));
+            } else {
+                let tag = tag.trim_end_matches('/');
+                let name_end = tag
+                    .find(|c: char| c.is_whitespace())
+                    .unwrap_or_else(|| tag.len());
+                tokens.push(Token::Open(
+                    tag[..name_end].to_ascii_lowercase(),
+                    &tag[name_end..],
+                ));
+            }
+        } else {
+            let end = rest.find('<').unwrap_or_else(|| rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+    tokens
+}
+
+///
+/// Get the value of an attribute from the attributes part of a tag.
+///
+/// # Arguments
+///
+/// * `attributes` - attributes of the tag. Example: ` class="day-desc" id="part2"`
+/// * `name` - the attribute name. Example: `class`
+///
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attributes;
+    while let Some(position) = rest.find(name) {
+        let before = rest[..position].chars().last();
+        let after = rest[position + name.len()..].trim_start();
+        rest = &rest[position + name.len()..];
+        if before.map_or(true, char::is_whitespace) && after.starts_with('=') {
+            let value = after[1..].trim_start();
+            let quote = value.chars().next()?;
+            if quote == '"' || quote == '\'' {
+                let value = &value[1..];
+                return value.find(quote).map(|end| &value[..end]);
+            }
+            let end = value
+                .find(|c: char| c.is_whitespace())
+                .unwrap_or_else(|| value.len());
+            return Some(&value[..end]);
+        }
+    }
+    None
+}
+
+///
+/// Replace html entities by the characters they stand for.
+///
+/// # Arguments
+///
+/// * `text` - a text with entities. Example: `a &lt; b`
+///
+pub fn decode_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find(';') {
+            Some(end) if end <= 10 => end,
+            _ => {
+                result.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let decoded = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            entity if entity.starts_with("#x") || entity.starts_with("#X") => {
+                u32::from_str_radix(&entity[2..], 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+            }
+            entity if entity.starts_with('#') => entity[1..]
+                .parse::<u32>()
+                .ok()
+                .and_then(std::char::from_u32),
+            _ => None,
+        };
+        match decoded {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+///
+/// Extract the inner html of the puzzle statements from the puzzle page.
+/// The first element is the part 1 statement, the second one (if the part 1 was solved
+/// when the page was saved) is the part 2 statement.
+///
+/// # Arguments
+///
+/// * `html` - the saved puzzle page. Data example:
+///
+/// <main><article class="day-desc"><h2>--- Day 1: Chronal Calibration ---</h2><p>...</p></article>
+///
+pub fn extract_parts(html: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<article") {
+        rest = &rest[start..];
+        let tag_end = match rest.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        let is_description = attribute(&rest[8..tag_end], "class").map_or(false, |class| {
+            class.split_whitespace().any(|c| c == "day-desc")
+        });
+        rest = &rest[tag_end + 1..];
+        let end = rest.find("</article>").unwrap_or_else(|| rest.len());
+        if is_description {
+            parts.push(rest[..end].to_string());
+        }
+        rest = &rest[end..];
+    }
+    parts
+}
+
+///
+/// Convert the html of a puzzle statement into Markdown.
+/// Supports the tags the puzzle statements use: headers, paragraphs, lists, links,
+/// emphasis, inline code and preformatted blocks.
+///
+/// # Arguments
+///
+/// * `html` - the statement html. Data example:
+///
+/// <h2>--- Day 1: Chronal Calibration ---</h2><p>What is the <em>resulting frequency</em>?</p>
+///
+pub fn html_to_markdown(html: &str) -> String {
+    let mut result = String::new();
+    let mut links: Vec<String> = Vec::new();
+    let mut list_depth = 0usize;
+    let mut in_pre = false;
+    let mut in_code = false;
+    for token in tokenize(html) {
+        match token {
+            Token::Open(name, attributes) => match name.as_str() {
+                "h1" | "h2" | "h3" | "h4" => {
+                    let level = name[1..].parse::<usize>().unwrap_or(2);
+                    start_block(&mut result);
+                    result.push_str(&"#".repeat(level));
+                    result.push(' ');
+                }
+                "p" => start_block(&mut result),
+                "ul" | "ol" => {
+                    if list_depth == 0 {
+                        start_block(&mut result);
+                    }
+                    list_depth += 1;
+                }
+                "li" => {
+                    start_line(&mut result);
+                    result.push_str(&"  ".repeat(list_depth.saturating_sub(1)));
+                    result.push_str("- ");
+                }
+                "pre" => {
+                    start_block(&mut result);
+                    result.push_str("```\n");
+                    in_pre = true;
+                }
+                "code" if !in_pre => {
+                    result.push('`');
+                    in_code = true;
+                }
+                "em" | "b" | "strong" | "i" if !in_pre && !in_code => result.push('*'),
+                "a" => {
+                    result.push('[');
+                    links.push(attribute(attributes, "href").unwrap_or("").to_string());
+                }
+                "br" => result.push('\n'),
+                _ => {}
+            },
+            Token::Close(name) => match name.as_str() {
+                "h1" | "h2" | "h3" | "h4" | "p" => result.push_str("\n\n"),
+                "ul" | "ol" => {
+                    list_depth = list_depth.saturating_sub(1);
+                    if list_depth == 0 {
+                        result.push_str("\n\n");
+                    }
+                }
+                "pre" => {
+                    start_line(&mut result);
+                    result.push_str("```\n\n");
+                    in_pre = false;
+                }
+                "code" if !in_pre => {
+                    result.push('`');
+                    in_code = false;
+                }
+                "em" | "b" | "strong" | "i" if !in_pre && !in_code => result.push('*'),
+                "a" => {
+                    let href = links.pop().unwrap_or_default();
+                    result.push_str(&format!("]({})", href));
+                }
+                _ => {}
+            },
+            Token::Text(text) => {
+                let text = decode_entities(text);
+                if in_pre {
+                    result.push_str(&text);
+                } else if in_code {
+                    push_collapsed(&mut result, &text);
+                } else {
+                    push_collapsed(&mut result, &text.replace('*', "\\*"));
+                }
+            }
+        }
+    }
+    let mut markdown = result
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<&str>>()
+        .join("\n");
+    while markdown.contains("\n\n\n") {
+        markdown = markdown.replace("\n\n\n", "\n\n");
+    }
+    format!("{}\n", markdown.trim())
+}
+
+///
+/// Begin a new paragraph unless the text already ends with an empty line.
+///
+fn start_block(result: &mut String) {
+    if !result.is_empty() && !result.ends_with("\n\n") {
+        start_line(result);
+        result.push('\n');
+    }
+}
+
+///
+/// Begin a new line unless the text already ends with a line break.
+///
+fn start_line(result: &mut String) {
+    if !result.is_empty() && !result.ends_with('\n') {
+        result.push('\n');
+    }
+}
+
+///
+/// Append the text collapsing whitespace the way a browser does.
+///
+fn push_collapsed(result: &mut String, text: &str) {
+    for (i, word) in text.split_whitespace().enumerate() {
+        let at_line_start = result.is_empty() || result.ends_with('\n');
+        let needs_space = i > 0 || text.starts_with(char::is_whitespace);
+        if needs_space && !at_line_start && !result.ends_with(' ') {
+            result.push(' ');
+        }
+        result.push_str(word);
+    }
+    if text.ends_with(char::is_whitespace) && !text.trim().is_empty() && !result.ends_with('\n') {
+        result.push(' ');
+    }
+}
+
+///
+/// Convert the saved puzzle page to Markdown. Both parts are joined into one document.
+///
+/// # Arguments
+///
+/// * `html` - the saved puzzle page.
+///
+pub fn puzzle_to_markdown(html: &str) -> String {
+    extract_parts(html)
+        .iter()
+        .map(|part| html_to_markdown(part))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+///
+/// The puzzle title of the Markdown statement. Example: `## --- Day 1: Chronal Calibration ---`
+/// gives `Chronal Calibration`
+///
+/// # Arguments
+///
+/// * `markdown` - the puzzle statement made by `puzzle_to_markdown`.
+///
+pub fn puzzle_title(markdown: &str) -> Option<String> {
+    let heading = markdown.lines().find(|line| line.starts_with('#'))?;
+    let heading = heading.trim_start_matches('#').trim();
+    let heading = heading
+        .trim_start_matches("---")
+        .trim_end_matches("---")
+        .trim();
+    let (_, title) = heading.split_once(": ")?;
+    Some(title.trim().to_string())
+}
+
+///
+/// Convert the saved puzzle page of the day into Markdown and store it next to the input data.
+/// Returns the Markdown.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+/// * `day` - the puzzle day. The page must be saved to `{input_root}/day{day}_puzzle.html`
+///
+pub fn archive_puzzle(input_root: &str, day: u8) -> io::Result<String> {
+    let html = fs::read_to_string(puzzle_html_file_name(input_root, day))?;
+    let markdown = puzzle_to_markdown(&html);
+    if markdown.trim().is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "no puzzle statement found in '{}'",
+                puzzle_html_file_name(input_root, day)
+            ),
+        ));
+    }
+    fs::write(puzzle_markdown_file_name(input_root, day), &markdown)?;
+    Ok(markdown)
+}
+
+///
+/// Load the Markdown statement of the day. The Markdown is created from the saved puzzle page
+/// when it was not archived yet.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+/// * `day` - the puzzle day.
+///
+pub fn load_puzzle(input_root: &str, day: u8) -> io::Result<String> {
+    let markdown_file_name = puzzle_markdown_file_name(input_root, day);
+    if Path::new(&markdown_file_name).exists() {
+        fs::read_to_string(markdown_file_name)
+    } else {
+        archive_puzzle(input_root, day)
+    }
+}
+
+const TERMINAL_WIDTH: usize = 80;
+const STYLE_RESET: &str = "\x1b[0m";
+const STYLE_HEADER: &str = "\x1b[1;33m";
+const STYLE_EMPHASIS: &str = "\x1b[1;97m";
+const STYLE_CODE: &str = "\x1b[32m";
+
+///
+/// Render Markdown for reading in a terminal: paragraphs are wrapped, the Markdown
+/// markers are replaced by terminal styles (or removed when `color` is false).
+///
+/// # Arguments
+///
+/// * `markdown` - the Markdown statement.
+/// * `color` - use ANSI escape codes.
+///
+pub fn render_markdown(markdown: &str, color: bool) -> String {
+    let mut result = String::new();
+    let mut in_fence = false;
+    let mut paragraph: Vec<&str> = Vec::new();
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            flush_paragraph(&mut result, &mut paragraph, color);
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            result.push_str("    ");
+            result.push_str(&paint(line, STYLE_CODE, color));
+            result.push('\n');
+        } else if line.starts_with('#') {
+            flush_paragraph(&mut result, &mut paragraph, color);
+            let title = line.trim_start_matches('#').trim();
+            result.push_str(&paint(title, STYLE_HEADER, color));
+            result.push('\n');
+        } else if line.trim().is_empty() {
+            flush_paragraph(&mut result, &mut paragraph, color);
+            if !result.is_empty() && !result.ends_with("\n\n") {
+                result.push('\n');
+            }
+        } else if line.trim_start().starts_with("- ") {
+            flush_paragraph(&mut result, &mut paragraph, color);
+            paragraph.push(line);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    flush_paragraph(&mut result, &mut paragraph, color);
+    result
+}
+
+///
+/// Wrap the collected paragraph lines and append them to the result.
+///
+fn flush_paragraph(result: &mut String, paragraph: &mut Vec<&str>, color: bool) {
+    if paragraph.is_empty() {
+        return;
+    }
+    let first = paragraph[0];
+    let indent = first.len() - first.trim_start().len();
+    let (prefix, continuation) = if first.trim_start().starts_with("- ") {
+        (
+            format!("{}  - ", " ".repeat(indent)),
+            " ".repeat(indent + 4),
+        )
+    } else {
+        (String::new(), String::new())
+    };
+    let text = paragraph
+        .iter()
+        .map(|line| line.trim())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    let text = text.strip_prefix("- ").unwrap_or(&text);
+    let text = unlink(text);
+    let mut lines = vec![prefix];
+    for (i, word) in text.split_whitespace().enumerate() {
+        let current = lines.last_mut().unwrap();
+        if i == 0 {
+            current.push_str(word);
+        } else if current.chars().count() + 1 + word.chars().count() > TERMINAL_WIDTH {
+            lines.push(format!("{}{}", continuation, word));
+        } else {
+            current.push(' ');
+            current.push_str(word);
+        }
+    }
+    result.push_str(&style_inline(&lines.join("\n"), color));
+    result.push('\n');
+    paragraph.clear();
+}
+
+///
+/// Replace the Markdown links `[text](href)` outside the code spans by `text (href)`.
+///
+fn unlink(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_code = false;
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '\\' if !in_code => {
+                result.push(c);
+                if let Some(escaped) = rest.chars().next() {
+                    result.push(escaped);
+                    rest = &rest[escaped.len_utf8()..];
+                }
+            }
+            '`' => {
+                in_code = !in_code;
+                result.push(c);
+            }
+            '[' if !in_code => match split_link(rest) {
+                Some((link_text, href, after)) => {
+                    result.push_str(link_text);
+                    result.push_str(&format!(" ({})", href));
+                    rest = after;
+                }
+                None => result.push(c),
+            },
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+///
+/// Split `text](href)...` after the opening bracket into the link text, the href
+/// and the text after the link.
+///
+fn split_link(text: &str) -> Option<(&str, &str, &str)> {
+    let end = text.find("](")?;
+    let link_text = &text[..end];
+    if link_text.contains('[') || link_text.contains('`') {
+        return None;
+    }
+    let after_text = &text[end + 2..];
+    let href_end = after_text.find(')')?;
+    let href = &after_text[..href_end];
+    if href.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((link_text, href, &after_text[href_end + 1..]))
+}
+
+///
+/// Replace the inline Markdown markers (emphasis and code) by terminal styles.
+///
+fn style_inline(text: &str, color: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_code = false;
+    let mut in_emphasis = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if !in_code => result.extend(chars.next()),
+            '`' => {
+                in_code = !in_code;
+                if color {
+                    result.push_str(if in_code { STYLE_CODE } else { STYLE_RESET });
+                    if !in_code && in_emphasis {
+                        result.push_str(STYLE_EMPHASIS);
+                    }
+                }
+            }
+            '*' if !in_code => {
+                in_emphasis = !in_emphasis;
+                if color {
+                    result.push_str(if in_emphasis {
+                        STYLE_EMPHASIS
+                    } else {
+                        STYLE_RESET
+                    });
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    if color && (in_code || in_emphasis) {
+        result.push_str(STYLE_RESET);
+    }
+    result
+}
+
+fn paint(text: &str, style: &str, color: bool) -> String {
+    if color {
+        format!("{}{}{}", style, text, STYLE_RESET)
+    } else {
+        text.to_string()
+    }
+}
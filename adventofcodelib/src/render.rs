@@ -0,0 +1,250 @@
+use crate::fabric::Fabric;
+use crate::sweep::intact_claims;
+use std::fs;
+use std::path::Path;
+
+///
+/// The largest fabric side drawn as text
+///
+pub const TEXT_LIMIT: u64 = 200;
+
+///
+/// The largest count of pixels of a picture
+///
+pub const PICTURE_LIMIT: u64 = 1 << 24;
+
+const BACKGROUND: [u8; 3] = [24, 24, 24];
+const OVERLAP: [u8; 3] = [255, 48, 48];
+const OUTLINE: [u8; 3] = [255, 255, 255];
+
+///
+/// The drawn area: the bounding box of the claims with one square of margin
+///
+fn canvas(fabric: &Fabric, limit: u64) -> Result<(u64, u64, u64, u64), String> {
+    let (min_x, min_y, width, height) = fabric.bounds();
+    let (left, top) = (min_x.saturating_sub(1), min_y.saturating_sub(1));
+    let (width, height) = (min_x + width + 1 - left, min_y + height + 1 - top);
+    if width.max(height) > limit || width.saturating_mul(height) > PICTURE_LIMIT {
+        return Err(format!(
+            "The fabric of {}×{} square inches is too large to draw",
+            width, height
+        ));
+    }
+    Ok((left, top, width, height))
+}
+
+///
+/// The claim counts of the squares of the drawn area, row by row
+///
+fn claim_counts(fabric: &Fabric, left: u64, top: u64, width: u64, height: u64) -> Vec<u32> {
+    let mut counts = vec![0; (width * height) as usize];
+    for inch in fabric.claims() {
+        for y in inch.min_y..inch.max_y {
+            for x in inch.min_x..inch.max_x {
+                counts[((y - top) * width + (x - left)) as usize] += 1;
+            }
+        }
+    }
+    counts
+}
+
+///
+/// Draw the fabric as text: `.` is a free square, `X` a square within two or more claims,
+/// the other squares show the last base-36 digit of the claim id. Example:
+///
+/// ........
+///
+/// ...2222.
+///
+/// .11XX22.
+///
+/// .111133.
+///
+/// ........
+///
+pub fn ascii(fabric: &Fabric) -> Result<String, String> {
+    let (left, top, width, height) = canvas(fabric, TEXT_LIMIT)?;
+    let counts = claim_counts(fabric, left, top, width, height);
+    let mut rows = vec![vec!['.'; width as usize]; height as usize];
+    for inch in fabric.claims() {
+        let digit = std::char::from_digit(inch.id % 36, 36).expect("DAY3: a base-36 digit!");
+        for y in inch.min_y..inch.max_y {
+            for x in inch.min_x..inch.max_x {
+                let (row, column) = ((y - top) as usize, (x - left) as usize);
+                rows[row][column] = match counts[row * width as usize + column] {
+                    1 => digit,
+                    _ => 'X',
+                };
+            }
+        }
+    }
+    Ok(rows
+        .iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect())
+}
+
+///
+/// RGB image of the fabric, one pixel per square inch
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct Picture {
+    pub width: usize,
+    pub height: usize,
+    /// the pixels row by row
+    pub pixels: Vec<[u8; 3]>,
+}
+
+///
+/// The color of the claim, the same for the same id
+///
+fn claim_color(id: u32) -> [u8; 3] {
+    let hash = id.wrapping_mul(0x9e37_79b1).to_be_bytes();
+    // muted colors keep the overlaps and the outlines visible
+    [64 + hash[0] / 2, 64 + hash[1] / 2, 64 + hash[2] / 2]
+}
+
+///
+/// Draw the fabric: every claim in its own color, the overlaps highlighted
+/// and the intact claims outlined.
+///
+pub fn picture(fabric: &Fabric) -> Result<Picture, String> {
+    let (left, top, width, height) = canvas(fabric, u64::MAX)?;
+    let mut picture = Picture {
+        width: width as usize,
+        height: height as usize,
+        pixels: vec![BACKGROUND; (width * height) as usize],
+    };
+    let pixel = |x: u64, y: u64| ((y - top) * width + (x - left)) as usize;
+    let counts = claim_counts(fabric, left, top, width, height);
+    for inch in fabric.claims() {
+        let color = claim_color(inch.id);
+        for y in inch.min_y..inch.max_y {
+            for x in inch.min_x..inch.max_x {
+                picture.pixels[pixel(x, y)] = match counts[pixel(x, y)] {
+                    1 => color,
+                    _ => OVERLAP,
+                };
+            }
+        }
+    }
+    let intact = intact_claims(fabric.claims());
+    for inch in fabric.claims().iter().filter(|i| intact.contains(&i.id)) {
+        for y in inch.min_y..inch.max_y {
+            for x in inch.min_x..inch.max_x {
+                let is_edge = x == inch.min_x
+                    || x + 1 == inch.max_x
+                    || y == inch.min_y
+                    || y + 1 == inch.max_y;
+                if is_edge {
+                    picture.pixels[pixel(x, y)] = OUTLINE;
+                }
+            }
+        }
+    }
+    Ok(picture)
+}
+
+impl Picture {
+    ///
+    /// Binary PPM (P6) file content
+    ///
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.extend(self.pixels.iter().flatten());
+        ppm
+    }
+
+    ///
+    /// PNG file content, the image data is stored without compression
+    ///
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filter and no interlace
+        header.extend([8, 2, 0, 0, 0]);
+
+        // every row starts with the filter type 0 (none)
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+        let mut zlib = vec![0x78, 0x01];
+        let blocks = raw.chunks(0xffff).collect::<Vec<&[u8]>>();
+        for (i, block) in blocks.iter().enumerate() {
+            zlib.push(u8::from(i + 1 == blocks.len()));
+            zlib.extend((block.len() as u16).to_le_bytes());
+            zlib.extend((!(block.len() as u16)).to_le_bytes());
+            zlib.extend(*block);
+        }
+        if blocks.is_empty() {
+            zlib.extend([1, 0, 0, 0xff, 0xff]);
+        }
+        zlib.extend(adler32(&raw).to_be_bytes());
+
+        let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        for (kind, data) in [(b"IHDR", header), (b"IDAT", zlib), (b"IEND", Vec::new())] {
+            png.extend((data.len() as u32).to_be_bytes());
+            let start = png.len();
+            png.extend(kind);
+            png.extend(&data);
+            let crc = crc32(&png[start..]);
+            png.extend(crc.to_be_bytes());
+        }
+        png
+    }
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+///
+/// Draw the fabric into the file, the format is chosen by the file extension:
+/// `.txt` for text, `.ppm` or `.png` for a picture.
+///
+/// # Arguments
+///
+/// * `fabric` - the fabric with the claims.
+/// * `file` - a path to the output file. Example: `day3.png`
+///
+pub fn write(fabric: &Fabric, file: &Path) -> Result<(), String> {
+    let extension = file
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    let content = match extension.as_deref() {
+        Some("txt") => ascii(fabric)?.into_bytes(),
+        Some("ppm") => picture(fabric)?.to_ppm(),
+        Some("png") => picture(fabric)?.to_png(),
+        _ => {
+            return Err(format!(
+                "Cannot choose the format of '{}': use .txt, .ppm or .png",
+                file.display()
+            ))
+        }
+    };
+    fs::write(file, content).map_err(|e| format!("Cannot write '{}': {}", file.display(), e))
+}
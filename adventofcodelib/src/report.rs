@@ -0,0 +1,168 @@
+use crate::puzzle::{load_puzzle, puzzle_title};
+use crate::runner::{format_elapsed, RunReport};
+use crate::solutions::Solution;
+use std::fs;
+use std::time::Duration;
+
+///
+/// The line opening the generated section of the README
+///
+pub const SECTION_START: &str = "<!-- report:start -->";
+
+///
+/// The line closing the generated section of the README
+///
+pub const SECTION_END: &str = "<!-- report:end -->";
+
+///
+/// The results of a puzzle day
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayReport {
+    pub year: u16,
+    pub day: u8,
+    /// the title of the archived puzzle statement
+    pub title: Option<String>,
+    /// count of the solved parts
+    pub stars: u8,
+    /// run times of part 1 and part 2, `None` when the part is not solved
+    pub times: [Option<Duration>; 2],
+    /// a link to the source of the solutions relative to the repository root
+    pub source: String,
+}
+
+///
+/// The link to the solution function. Example: `adventofcodelib/src/lib.rs#L130`
+/// The line is looked up in the source file when it can be read from the current directory.
+///
+/// # Arguments
+///
+/// * `solution` - the solution.
+///
+pub fn source_link(solution: &Solution) -> String {
+    let function = format!("fn day{}_task{}(", solution.day, solution.part);
+    let line = fs::read_to_string(solution.source).ok().and_then(|source| {
+        source
+            .lines()
+            .position(|line| line.contains(&function))
+            .map(|i| i + 1)
+    });
+    match line {
+        Some(line) => format!("{}#L{}", solution.source, line),
+        None => solution.source.to_string(),
+    }
+}
+
+///
+/// Collect the run reports into one report per day.
+///
+/// # Arguments
+///
+/// * `solutions` - the solutions ordered by year, day and part.
+/// * `reports` - the run report of every solution.
+/// * `input_root` - the directory with input data and archived puzzles.
+///
+pub fn day_reports(
+    solutions: &[Solution],
+    reports: &[RunReport],
+    input_root: &str,
+) -> Vec<DayReport> {
+    let mut days: Vec<DayReport> = Vec::new();
+    for (solution, report) in solutions.iter().zip(reports) {
+        let is_new_day = match days.last() {
+            Some(d) => d.year != solution.year || d.day != solution.day,
+            None => true,
+        };
+        if is_new_day {
+            days.push(DayReport {
+                year: solution.year,
+                day: solution.day,
+                title: load_puzzle(input_root, solution.day)
+                    .ok()
+                    .and_then(|markdown| puzzle_title(&markdown)),
+                stars: 0,
+                times: [None, None],
+                source: source_link(solution),
+            });
+        }
+        let day = days.last_mut().expect("REPORT: the day is just added!");
+        if report.answer().is_some() && (1..=2).contains(&solution.part) {
+            day.stars += 1;
+            day.times[solution.part as usize - 1] = Some(report.elapsed);
+        }
+    }
+    days
+}
+
+///
+/// Render the day reports as a Markdown table.
+///
+pub fn markdown_table(days: &[DayReport]) -> String {
+    let mut table = String::from(
+        "| Year | Day | Puzzle | Stars | Part 1 | Part 2 | Source |\n\
+         |------|----:|--------|-------|-------:|-------:|--------|\n",
+    );
+    for day in days {
+        let url = format!("https://adventofcode.com/{}/day/{}", day.year, day.day);
+        let title = day
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("Day {}", day.day));
+        let time = |time: Option<Duration>| time.map_or(String::from("-"), format_elapsed);
+        table.push_str(&format!(
+            "| {} | {} | [{}]({}) | {} | {} | {} | [source]({}) |\n",
+            day.year,
+            day.day,
+            title.replace('|', "\\|"),
+            url,
+            "★".repeat(day.stars as usize),
+            time(day.times[0]),
+            time(day.times[1]),
+            day.source
+        ));
+    }
+    table
+}
+
+///
+/// Replace the generated section of the document with the table. The section is appended
+/// to the document when it has no section markers yet.
+///
+/// # Arguments
+///
+/// * `document` - the README content.
+/// * `table` - the generated Markdown.
+///
+pub fn replace_section(document: &str, table: &str) -> Result<String, String> {
+    let section = format!("{}\n{}{}\n", SECTION_START, table, SECTION_END);
+    match (document.find(SECTION_START), document.find(SECTION_END)) {
+        (Some(start), Some(end)) if start < end => {
+            let end = end + SECTION_END.len();
+            let end = if document[end..].starts_with('\n') {
+                end + 1
+            } else {
+                end
+            };
+            Ok(format!(
+                "{}{}{}",
+                &document[..start],
+                section,
+                &document[end..]
+            ))
+        }
+        (None, None) => {
+            let separator = if document.is_empty() || document.ends_with("\n\n") {
+                ""
+            } else if document.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            Ok(format!("{}{}{}", document, separator, section))
+        }
+        _ => Err(format!(
+            "The report section markers '{}' and '{}' are broken",
+            SECTION_START, SECTION_END
+        )),
+    }
+}
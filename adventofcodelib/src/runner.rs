@@ -0,0 +1,214 @@
+use crate::alloc_stats::{self, AllocStats};
+use crate::cancel::{self, CancellationToken};
+use crate::progress::{self, ProgressReporter};
+use crate::solutions::Solution;
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+///
+/// How a solution run ended
+///
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Solved(String),
+    /// the solution did not finish within the time limit and was asked to stop
+    TimedOut(Duration),
+    Panicked(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Solved(answer) => write!(f, "{}", answer),
+            Outcome::TimedOut(limit) => write!(f, "timed out after {}", format_elapsed(*limit)),
+            Outcome::Panicked(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+///
+/// The answer of a solution and what it cost
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunReport {
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+    /// `None` when the counting allocator is not installed or the run did not finish
+    pub alloc: Option<AllocStats>,
+}
+
+impl RunReport {
+    pub fn answer(&self) -> Option<&str> {
+        match &self.outcome {
+            Outcome::Solved(answer) => Some(answer),
+            _ => None,
+        }
+    }
+}
+
+///
+/// Run the solution on its input data in a separate thread. When the time limit is over
+/// the solution is asked to stop (see `cancel::is_cancelled`) and the run is reported as
+/// timed out without waiting for it.
+///
+/// # Arguments
+///
+/// * `solution` - the solution to run.
+/// * `limit` - the wall-clock time limit, `None` to wait as long as it takes.
+///
+pub fn run(solution: &Solution, limit: Option<Duration>) -> RunReport {
+    run_with_progress(solution, limit, None)
+}
+
+///
+/// Run the solution like `run` does, sending the progress the solution reports
+/// to the reporter.
+///
+/// # Arguments
+///
+/// * `solution` - the solution to run.
+/// * `limit` - the wall-clock time limit, `None` to wait as long as it takes.
+/// * `reporter` - receives the progress, `None` to ignore it.
+///
+pub fn run_with_progress(
+    solution: &Solution,
+    limit: Option<Duration>,
+    reporter: Option<Arc<dyn ProgressReporter>>,
+) -> RunReport {
+    let token = CancellationToken::new();
+    let (sender, receiver) = mpsc::channel();
+    let solve = solution.solve;
+    let file_name = solution.file_name.clone();
+    let worker_token = token.clone();
+    let start = Instant::now();
+    thread::Builder::new()
+        .name(solution.name())
+        .spawn(move || {
+            let solve = || {
+                cancel::with_token(worker_token, || {
+                    alloc_stats::measure(|| {
+                        panic::catch_unwind(AssertUnwindSafe(|| solve(&file_name)))
+                    })
+                })
+            };
+            let result = match reporter {
+                Some(reporter) => progress::with_reporter(reporter, solve),
+                None => solve(),
+            };
+            // nobody listens when the run has timed out
+            let _ = sender.send((result, start.elapsed()));
+        })
+        .expect("RUNNER: Cannot start a thread!");
+    let received = match limit {
+        Some(limit) => receiver.recv_timeout(limit),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+    match received {
+        Ok(((Ok(answer), alloc), elapsed)) => RunReport {
+            outcome: Outcome::Solved(answer),
+            elapsed,
+            alloc,
+        },
+        Ok(((Err(payload), _), elapsed)) => RunReport {
+            outcome: Outcome::Panicked(panic_message(payload.as_ref())),
+            elapsed,
+            alloc: None,
+        },
+        Err(RecvTimeoutError::Timeout) => {
+            token.cancel();
+            RunReport {
+                outcome: Outcome::TimedOut(limit.unwrap_or_default()),
+                elapsed: start.elapsed(),
+                alloc: None,
+            }
+        }
+        Err(RecvTimeoutError::Disconnected) => RunReport {
+            outcome: Outcome::Panicked(String::from("the solution thread stopped")),
+            elapsed: start.elapsed(),
+            alloc: None,
+        },
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+///
+/// Timings of several runs of a solution
+///
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchReport {
+    pub iterations: u32,
+    pub min: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+    /// heap usage of a single run, `None` when the counting allocator is not installed
+    pub alloc: Option<AllocStats>,
+}
+
+///
+/// Run the solution several times and collect the timings. Stops at the first run which
+/// did not solve the task and returns its report as the error.
+///
+/// # Arguments
+///
+/// * `solution` - the solution to run.
+/// * `iterations` - how many times to run it. Must be positive.
+/// * `limit` - the wall-clock time limit of a single run.
+///
+pub fn bench(
+    solution: &Solution,
+    iterations: u32,
+    limit: Option<Duration>,
+) -> Result<BenchReport, RunReport> {
+    assert!(iterations > 0, "BENCH: iterations must be positive!");
+    let mut total = Duration::default();
+    let mut min = Duration::MAX;
+    let mut max = Duration::default();
+    let mut alloc = None;
+    for _ in 0..iterations {
+        let report = run(solution, limit);
+        if report.answer().is_none() {
+            return Err(report);
+        }
+        total += report.elapsed;
+        min = min.min(report.elapsed);
+        max = max.max(report.elapsed);
+        alloc = report.alloc;
+    }
+    Ok(BenchReport {
+        iterations,
+        min,
+        mean: total / iterations,
+        max,
+        alloc,
+    })
+}
+
+///
+/// Format a duration for humans. Example: `1.25ms`
+///
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let nanos = elapsed.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.2}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
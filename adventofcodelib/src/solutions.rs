@@ -0,0 +1,123 @@
+use crate::*;
+
+///
+/// A task of a puzzle day and the function solving it
+///
+#[derive(Clone)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    /// a path to the file with input data
+    pub file_name: String,
+    pub solve: fn(&str) -> String,
+    /// the name of the implementation, the tasks solved in several ways have several variants
+    pub variant: &'static str,
+    /// tells how the answer was derived, for the solutions which can do it
+    pub explain: Option<fn(&str) -> String>,
+    /// the source file of the solution relative to the repository root
+    pub source: &'static str,
+    /// changes with the solver code, cached answers of other versions are not used
+    pub version: &'static str,
+}
+
+impl Solution {
+    ///
+    /// The name used in the run output. Example: `day1 first task`, `day5 second task [fast]`
+    /// for the other variants.
+    ///
+    pub fn name(&self) -> String {
+        let part = match self.part {
+            1 => "first",
+            2 => "second",
+            _ => "unknown",
+        };
+        if self.variant == DEFAULT_VARIANT {
+            format!("day{} {} task", self.day, part)
+        } else {
+            format!("day{} {} task [{}]", self.day, part, self.variant)
+        }
+    }
+}
+
+///
+/// The hash of the solver sources made by the build script
+///
+pub const SOLVER_VERSION: &str = env!("AOC_SOLVER_VERSION");
+
+///
+/// The name of the first implementation of a task
+///
+pub const DEFAULT_VARIANT: &str = "naive";
+
+///
+/// All solved tasks ordered by year, day and part, reading input data from `INPUT_ROOT`.
+///
+pub fn solutions() -> Vec<Solution> {
+    solutions_in(INPUT_ROOT)
+}
+
+///
+/// All solved tasks ordered by year, day and part, the default variant of every task.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+///
+pub fn solutions_in(input_root: &str) -> Vec<Solution> {
+    let mut solutions = variants_in(input_root);
+    solutions.dedup_by_key(|s| (s.year, s.day, s.part));
+    solutions
+}
+
+///
+/// All variants of the solved tasks ordered by year, day and part. The default variant
+/// of a task goes first.
+///
+/// # Arguments
+///
+/// * `input_root` - the directory with input data.
+///
+pub fn variants_in(input_root: &str) -> Vec<Solution> {
+    let solution = |day, part, solve| Solution {
+        year: 2018,
+        day,
+        part,
+        file_name: input_file_name(input_root, day),
+        solve,
+        variant: DEFAULT_VARIANT,
+        explain: None,
+        source: "adventofcodelib/src/lib.rs",
+        version: SOLVER_VERSION,
+    };
+    let explained = |day, part, solve, explain| Solution {
+        explain: Some(explain),
+        ..solution(day, part, solve)
+    };
+    let variant = |day, part, variant, solve| Solution {
+        variant,
+        ..solution(day, part, solve)
+    };
+    vec![
+        solution(1, 1, |f| day1_task1(f).to_string()),
+        solution(1, 2, |f| {
+            day1_task2(f)
+                .expect("DAY1: no frequency is reached twice!")
+                .frequency
+                .to_string()
+        }),
+        solution(2, 1, |f| day2_task1(f).to_string()),
+        explained(2, 2, day2_task2, day2_task2_explain),
+        variant(2, 2, "fast", day2_task2_fast),
+        solution(3, 1, |f| day3_task1(f).to_string()),
+        variant(3, 1, "sweep", |f| day3_task1_sweep(f).to_string()),
+        solution(3, 2, |f| day3_task2(f).to_string()),
+        variant(3, 2, "sweep", |f| day3_task2_sweep(f).to_string()),
+        explained(4, 1, |f| day4_task1(f).to_string(), day4_task1_explain),
+        explained(4, 2, |f| day4_task2(f).to_string(), day4_task2_explain),
+        solution(5, 1, |f| day5_task1(f).to_string()),
+        explained(5, 2, |f| day5_task2(f).to_string(), day5_task2_explain),
+        variant(5, 2, "fast", |f| day5_task2_fast(f).to_string()),
+        variant(5, 2, "parallel", |f| day5_task2_parallel(f).to_string()),
+    ]
+}
@@ -0,0 +1,199 @@
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{AeadCore, ChaCha20Poly1305, Key, Nonce};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+///
+/// The environment variable holding the input key in hex
+///
+pub const KEY_ENV: &str = "AOC_INPUT_KEY";
+
+///
+/// The environment variable holding the path to the input key file
+///
+pub const KEY_FILE_ENV: &str = "AOC_INPUT_KEY_FILE";
+
+///
+/// The name of the key file in the user configuration directory
+///
+pub const KEY_FILE_NAME: &str = "input.key";
+
+const NONCE_LEN: usize = 12;
+
+///
+/// The symmetric key the puzzle inputs are encrypted with (ChaCha20-Poly1305)
+///
+#[derive(Clone, PartialEq)]
+pub struct InputKey([u8; 32]);
+
+impl InputKey {
+    ///
+    /// A new random key.
+    ///
+    pub fn generate() -> Self {
+        InputKey(ChaCha20Poly1305::generate_key(&mut OsRng).into())
+    }
+
+    ///
+    /// Parse the key written as 64 hex digits.
+    ///
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let hex = hex.trim();
+        let invalid = || String::from("The input key must be 64 hex digits");
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let mut key = [0u8; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+        }
+        Ok(InputKey(key))
+    }
+
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    ///
+    /// Load the key: `$AOC_INPUT_KEY`, otherwise the file `$AOC_INPUT_KEY_FILE`, otherwise
+    /// `input.key` in the user configuration directory. Returns `None` when there is no key.
+    ///
+    /// # Arguments
+    ///
+    /// * `var` - reads an environment variable.
+    ///
+    pub fn load<F: Fn(&str) -> Option<String>>(var: F) -> Result<Option<Self>, String> {
+        if let Some(hex) = var(KEY_ENV) {
+            return InputKey::from_hex(&hex)
+                .map(Some)
+                .map_err(|e| format!("{} (${})", e, KEY_ENV));
+        }
+        let key_file = match var(KEY_FILE_ENV) {
+            Some(path) => PathBuf::from(path),
+            None => match crate::config::user_config_dir(var) {
+                Some(dir) if dir.join(KEY_FILE_NAME).is_file() => dir.join(KEY_FILE_NAME),
+                _ => return Ok(None),
+            },
+        };
+        let hex = fs::read_to_string(&key_file)
+            .map_err(|e| format!("Cannot read the key file '{}': {}", key_file.display(), e))?;
+        InputKey::from_hex(&hex)
+            .map(Some)
+            .map_err(|e| format!("{} ('{}')", e, key_file.display()))
+    }
+
+    ///
+    /// Load the key from the process environment.
+    ///
+    pub fn from_env() -> Result<Option<Self>, String> {
+        InputKey::load(|name| env::var(name).ok())
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.0))
+    }
+}
+
+///
+/// Path to the encrypted copy of the input file. Example: `./input_data/day1_data.txt.enc`
+///
+pub fn encrypted_file_name(file_name: &str) -> String {
+    format!("{}.enc", file_name)
+}
+
+///
+/// Encrypt the data with a random nonce. The result is the nonce followed by the ciphertext.
+///
+pub fn encrypt(key: &InputKey, plain: &[u8]) -> Vec<u8> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let mut result = nonce.to_vec();
+    result.extend(
+        key.cipher()
+            .encrypt(&nonce, plain)
+            .expect("STORE: Cannot encrypt the input!"),
+    );
+    result
+}
+
+///
+/// Decrypt the data made by `encrypt`. Fails when the key is wrong or the data is damaged.
+///
+pub fn decrypt(key: &InputKey, data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < NONCE_LEN {
+        return Err(String::from("The encrypted input is too short"));
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    key.cipher()
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| String::from("Cannot decrypt the input: wrong key or damaged file"))
+}
+
+///
+/// Write the encrypted copy of the input file next to it. Returns the encrypted file path.
+///
+/// # Arguments
+///
+/// * `key` - the input key.
+/// * `file_name` - a path to the file with input data.
+///
+pub fn encrypt_file(key: &InputKey, file_name: &str) -> io::Result<String> {
+    let encrypted = encrypted_file_name(file_name);
+    fs::write(&encrypted, encrypt(key, &fs::read(file_name)?))?;
+    Ok(encrypted)
+}
+
+///
+/// Restore the input file from its encrypted copy.
+///
+/// # Arguments
+///
+/// * `key` - the input key.
+/// * `file_name` - a path to the file with input data.
+///
+pub fn decrypt_file(key: &InputKey, file_name: &str) -> io::Result<()> {
+    let data = fs::read(encrypted_file_name(file_name))?;
+    let plain = decrypt(key, &data).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(file_name, plain)
+}
+
+///
+/// Read the encrypted copy of the input file with the key from the environment.
+/// Returns `Ok(None)` when there is no encrypted copy.
+///
+/// # Arguments
+///
+/// * `file_name` - a path to the file with input data.
+///
+pub fn read_encrypted(file_name: &str) -> Result<Option<String>, String> {
+    let encrypted = encrypted_file_name(file_name);
+    if !Path::new(&encrypted).is_file() {
+        return Ok(None);
+    }
+    let key = InputKey::from_env()?.ok_or_else(|| {
+        format!(
+            "'{}' is encrypted, set ${} or ${} to read it",
+            encrypted, KEY_ENV, KEY_FILE_ENV
+        )
+    })?;
+    let data = fs::read(&encrypted).map_err(|e| format!("Cannot read '{}': {}", encrypted, e))?;
+    let plain = decrypt(&key, &data).map_err(|e| format!("{} ('{}')", e, encrypted))?;
+    String::from_utf8(plain)
+        .map(Some)
+        .map_err(|_| format!("'{}' is not a text file", encrypted))
+}
+
+///
+/// Can the input be read: the plain file exists, the encrypted copy can be decrypted
+/// or the input is embedded into the binary.
+///
+/// # Arguments
+///
+/// * `file_name` - a path to the file with input data.
+///
+pub fn is_available(file_name: &str) -> bool {
+    Path::new(file_name).is_file()
+        || matches!(read_encrypted(file_name), Ok(Some(_)))
+        || crate::embedded::embedded_input_for(file_name).is_some()
+}
@@ -0,0 +1,365 @@
+use crate::{cancel, progress, Inch};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+///
+/// Segment tree over the compressed y coordinates: the length covered by one
+/// and by two or more of the claims crossing the sweep line
+///
+struct CoverTree {
+    ys: Vec<u64>,
+    // how many claims cover the whole node range and nothing more above
+    counts: Vec<u32>,
+    once: Vec<u64>,
+    twice: Vec<u64>,
+}
+
+impl CoverTree {
+    fn new(ys: Vec<u64>) -> Self {
+        let size = 4 * ys.len().max(1);
+        CoverTree {
+            ys,
+            counts: vec![0; size],
+            once: vec![0; size],
+            twice: vec![0; size],
+        }
+    }
+
+    ///
+    /// Add `delta` claims over `[min_y, max_y)`.
+    ///
+    fn update(&mut self, min_y: u64, max_y: u64, delta: i32) {
+        let from = self.ys.partition_point(|&y| y < min_y);
+        let to = self.ys.partition_point(|&y| y < max_y);
+        if from < to && self.ys.len() > 1 {
+            self.update_node(1, 0, self.ys.len() - 1, from, to, delta);
+        }
+    }
+
+    // the node covers the slabs `lower..upper` between the compressed coordinates
+    fn update_node(
+        &mut self,
+        node: usize,
+        lower: usize,
+        upper: usize,
+        from: usize,
+        to: usize,
+        delta: i32,
+    ) {
+        if to <= lower || upper <= from {
+            return;
+        }
+        if from <= lower && upper <= to {
+            self.counts[node] = self.counts[node].saturating_add_signed(delta);
+        } else {
+            let middle = (lower + upper) / 2;
+            self.update_node(2 * node, lower, middle, from, to, delta);
+            self.update_node(2 * node + 1, middle, upper, from, to, delta);
+        }
+        let length = self.ys[upper] - self.ys[lower];
+        let is_leaf = upper - lower == 1;
+        let (children_once, children_twice) = if is_leaf {
+            (0, 0)
+        } else {
+            (
+                self.once[2 * node] + self.once[2 * node + 1],
+                self.twice[2 * node] + self.twice[2 * node + 1],
+            )
+        };
+        (self.once[node], self.twice[node]) = match self.counts[node] {
+            0 => (children_once, children_twice),
+            1 => (length, children_once),
+            _ => (length, length),
+        };
+    }
+
+    fn covered_twice(&self) -> u64 {
+        self.twice[1]
+    }
+}
+
+///
+/// Count of the square inches within two or more claims. The sweep line moves along x
+/// over the claim edges, the segment tree over the compressed y coordinates keeps
+/// the length of the line covered twice. O(n log n), the fabric size does not matter.
+///
+/// # Arguments
+///
+/// * `inches` - the claims.
+///
+pub fn overlap_area(inches: &[Inch]) -> u64 {
+    let claims = inches
+        .iter()
+        .filter(|inch| inch.area() > 0)
+        .collect::<Vec<&Inch>>();
+    let mut ys = claims
+        .iter()
+        .flat_map(|inch| [inch.min_y, inch.max_y])
+        .collect::<Vec<u64>>();
+    ys.sort_unstable();
+    ys.dedup();
+    // (x, the claim starts or ends, the claim)
+    let mut events = claims
+        .iter()
+        .flat_map(|inch| [(inch.min_x, 1, *inch), (inch.max_x, -1, *inch)])
+        .collect::<Vec<(u64, i32, &Inch)>>();
+    events.sort_unstable_by_key(|&(x, delta, _)| (x, delta));
+
+    let mut tree = CoverTree::new(ys);
+    let mut area = 0;
+    let mut previous_x = events.first().map_or(0, |&(x, _, _)| x);
+    for (i, &(x, delta, inch)) in events.iter().enumerate() {
+        progress::report("sweeping claims", i, events.len());
+        area += tree.covered_twice() * (x - previous_x);
+        tree.update(inch.min_y, inch.max_y, delta);
+        previous_x = x;
+    }
+    area
+}
+
+///
+/// The claim counts along the sweep line: a count starts at every key and lasts up to
+/// the next key. The neighbour segments with the same count are merged, so there are
+/// no more segments than the edges of the claims crossing the line.
+///
+#[derive(Default)]
+struct Segments {
+    starts: BTreeMap<u64, u32>,
+}
+
+impl Segments {
+    fn count_at(&self, y: u64) -> u32 {
+        self.starts
+            .range(..=y)
+            .next_back()
+            .map_or(0, |(_, &count)| count)
+    }
+
+    fn split_at(&mut self, y: u64) {
+        if !self.starts.contains_key(&y) {
+            let count = self.count_at(y);
+            self.starts.insert(y, count);
+        }
+    }
+
+    fn merge_at(&mut self, y: u64) {
+        let before = self
+            .starts
+            .range(..y)
+            .next_back()
+            .map_or(0, |(_, &count)| count);
+        if self.starts.get(&y) == Some(&before) {
+            self.starts.remove(&y);
+        }
+    }
+
+    ///
+    /// Add the claim to the line or remove it from the line.
+    ///
+    fn update(&mut self, min_y: u64, max_y: u64, starts: bool) {
+        self.split_at(min_y);
+        self.split_at(max_y);
+        for (_, count) in self.starts.range_mut(min_y..max_y) {
+            if starts {
+                *count += 1;
+            } else {
+                *count -= 1;
+            }
+        }
+        self.merge_at(min_y);
+        self.merge_at(max_y);
+    }
+
+    ///
+    /// The claimed segments of the line with their claim counts.
+    ///
+    fn claimed(&self) -> impl Iterator<Item = (Range<u64>, u32)> + '_ {
+        self.starts
+            .iter()
+            .zip(self.starts.keys().skip(1))
+            .filter(|((_, &count), _)| count > 0)
+            .map(|((&start, &count), &end)| (start..end, count))
+    }
+}
+
+///
+/// Visit the claimed parts of the fabric as rectangles with the same claim count,
+/// by their left edge and then by their top edge. The sweep line moves along x over
+/// the claim edges and keeps the claim counts of its segments only, so the memory
+/// does not depend on the fabric size or the number of the regions.
+///
+/// # Arguments
+///
+/// * `inches` - the claims.
+/// * `visit` - called with the x range, the y range and the claim count of every region.
+///
+pub fn for_each_region<F: FnMut(Range<u64>, Range<u64>, u32)>(inches: &[Inch], mut visit: F) {
+    let mut events = inches
+        .iter()
+        .filter(|inch| inch.area() > 0)
+        .flat_map(|inch| [(inch.min_x, true, inch), (inch.max_x, false, inch)])
+        .collect::<Vec<(u64, bool, &Inch)>>();
+    events.sort_unstable_by_key(|&(x, starts, _)| (x, starts));
+    let mut line = Segments::default();
+    for (i, &(x, starts, inch)) in events.iter().enumerate() {
+        progress::report("sweeping claims", i, events.len());
+        if cancel::is_cancelled() {
+            break;
+        }
+        line.update(inch.min_y, inch.max_y, starts);
+        match events.get(i + 1) {
+            Some(&(next_x, _, _)) if next_x > x => {
+                for (ys, count) in line.claimed() {
+                    visit(x..next_x, ys, count);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+///
+/// The count of the square inches by the number of claims covering them, see
+/// `for_each_region`.
+///
+/// # Arguments
+///
+/// * `inches` - the claims.
+///
+pub fn coverage(inches: &[Inch]) -> BTreeMap<u32, u64> {
+    let mut areas = BTreeMap::new();
+    for_each_region(inches, |xs, ys, count| {
+        *areas.entry(count).or_insert(0) += (xs.end - xs.start) * (ys.end - ys.start);
+    });
+    areas
+}
+
+///
+/// Fenwick tree of counts over the compressed coordinates
+///
+struct Counts {
+    tree: Vec<i64>,
+}
+
+impl Counts {
+    fn new(size: usize) -> Self {
+        Counts {
+            tree: vec![0; size + 1],
+        }
+    }
+
+    fn add(&mut self, position: usize, delta: i64) {
+        let mut i = position + 1;
+        while i < self.tree.len() {
+            self.tree[i] += delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    ///
+    /// The sum of the counts at the positions before `end`.
+    ///
+    fn sum_before(&self, end: usize) -> i64 {
+        let mut i = end;
+        let mut sum = 0;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+}
+
+///
+/// The claims added so far by their top and bottom edge. A claim crosses `[min_y, max_y)`
+/// unless it ends above `min_y` or starts at `max_y` or below, so the count of the crossing
+/// claims takes two prefix sums.
+///
+struct Spans<'a> {
+    ys: &'a [u64],
+    starts: Counts,
+    ends: Counts,
+}
+
+impl<'a> Spans<'a> {
+    fn new(ys: &'a [u64]) -> Self {
+        Spans {
+            ys,
+            starts: Counts::new(ys.len()),
+            ends: Counts::new(ys.len()),
+        }
+    }
+
+    fn rank(&self, y: u64) -> usize {
+        self.ys.partition_point(|&edge| edge < y)
+    }
+
+    fn add(&mut self, inch: &Inch, delta: i64) {
+        self.starts.add(self.rank(inch.min_y), delta);
+        self.ends.add(self.rank(inch.max_y), delta);
+    }
+
+    fn crossing(&self, inch: &Inch) -> i64 {
+        self.starts.sum_before(self.rank(inch.max_y))
+            - self.ends.sum_before(self.rank(inch.min_y) + 1)
+    }
+}
+
+///
+/// The ids of the claims overlapping no other claim, in the input order. The claims are
+/// swept by their x edges. A claim is overlapped when it starts while a claim crossing
+/// its y range is on the sweep line, or when such a claim starts before it ends.
+/// The claims on the sweep line and all the claims started so far are counted by their
+/// y edges, so it takes O(n log n) whatever the claims look like.
+///
+/// # Arguments
+///
+/// * `inches` - the claims.
+///
+pub fn intact_claims(inches: &[Inch]) -> Vec<u32> {
+    let claims = (0..inches.len())
+        .filter(|&i| inches[i].area() > 0)
+        .collect::<Vec<usize>>();
+    let mut ys = claims
+        .iter()
+        .flat_map(|&i| [inches[i].min_y, inches[i].max_y])
+        .collect::<Vec<u64>>();
+    ys.sort_unstable();
+    ys.dedup();
+    // (x, the claim starts, the claim): the claims ending at x leave before the others start
+    let mut events = claims
+        .iter()
+        .flat_map(|&i| [(inches[i].min_x, true, i), (inches[i].max_x, false, i)])
+        .collect::<Vec<(u64, bool, usize)>>();
+    events.sort_unstable();
+
+    let mut overlapped = vec![false; inches.len()];
+    let mut active = Spans::new(&ys);
+    let mut started = Spans::new(&ys);
+    // the claims crossing the y range of the claim started before it
+    let mut started_before = vec![0; inches.len()];
+    for (n, &(_, starts, i)) in events.iter().enumerate() {
+        progress::report("sweeping claims", n, events.len());
+        let inch = &inches[i];
+        if starts {
+            if active.crossing(inch) > 0 {
+                overlapped[i] = true;
+            }
+            started_before[i] = started.crossing(inch);
+            active.add(inch, 1);
+            started.add(inch, 1);
+        } else {
+            active.add(inch, -1);
+            // the claim itself is among the started ones
+            if started.crossing(inch) - 1 > started_before[i] {
+                overlapped[i] = true;
+            }
+        }
+    }
+    inches
+        .iter()
+        .zip(overlapped)
+        .filter(|(_, overlapped)| !overlapped)
+        .map(|(inch, _)| inch.id)
+        .collect()
+}
@@ -0,0 +1,169 @@
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitStr};
+
+///
+/// A piece of the line pattern: literal text or a `{field}` placeholder
+///
+enum Piece {
+    Literal(String),
+    Field(String),
+}
+
+///
+/// Split the pattern into pieces. `{{` and `}}` are literal braces.
+///
+/// # Arguments
+///
+/// * `pattern` - the line pattern. Example: `#{id} @ {left},{top}: {wide}x{toll}`
+///
+fn parse_pattern(pattern: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(format!("unclosed '{{{}' in the pattern", name)),
+                    }
+                }
+                if !literal.is_empty() {
+                    pieces.push(Piece::Literal(literal.clone()));
+                    literal.clear();
+                } else if let Some(Piece::Field(previous)) = pieces.last() {
+                    return Err(format!(
+                        "fields '{}' and '{}' must be separated by some text",
+                        previous, name
+                    ));
+                }
+                pieces.push(Piece::Field(name));
+            }
+            '}' => return Err(String::from("unmatched '}' in the pattern, use '}}'")),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        pieces.push(Piece::Literal(literal));
+    }
+    Ok(pieces)
+}
+
+///
+/// Derive `FromStr` for a struct with named fields from its line pattern:
+///
+/// #[derive(LinePattern)]
+/// #[line_pattern("#{id} @ {left},{top}: {wide}x{toll}")]
+/// struct Claim { id: u32, left: u32, top: u32, wide: u32, toll: u32 }
+///
+/// Every field appears in the pattern once and is parsed with its own `FromStr`.
+/// A space in the pattern matches zero or more whitespace characters, the last field
+/// takes the rest of the line. The error is `adventofcodelib::line::LineError`.
+///
+#[proc_macro_derive(LinePattern, attributes(line_pattern))]
+pub fn derive_line_pattern(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    name,
+                    "LinePattern needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "LinePattern can be derived for structs only",
+            ))
+        }
+    };
+    let attribute = input
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("line_pattern"))
+        .ok_or_else(|| Error::new_spanned(name, "missing #[line_pattern(\"...\")] attribute"))?;
+    let pattern: LitStr = attribute.parse_args()?;
+    let pieces = parse_pattern(&pattern.value()).map_err(|e| Error::new_spanned(&pattern, e))?;
+
+    let mut field_names: Vec<String> = Vec::new();
+    for piece in &pieces {
+        if let Piece::Field(field) = piece {
+            if field_names.contains(field) {
+                return Err(Error::new_spanned(
+                    &pattern,
+                    format!("field '{}' appears in the pattern twice", field),
+                ));
+            }
+            if !fields
+                .iter()
+                .any(|f| f.ident.as_ref().is_some_and(|i| i == field))
+            {
+                return Err(Error::new_spanned(
+                    &pattern,
+                    format!("'{}' has no field '{}'", name, field),
+                ));
+            }
+            field_names.push(field.clone());
+        }
+    }
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        if !field_names.iter().any(|f| ident == f) {
+            return Err(Error::new_spanned(
+                ident,
+                format!("field '{}' is missing in the line pattern", ident),
+            ));
+        }
+    }
+
+    let piece_tokens = pieces.iter().map(|piece| match piece {
+        Piece::Literal(text) => quote! { ::adventofcodelib::line::Piece::Literal(#text) },
+        Piece::Field(field) => quote! { ::adventofcodelib::line::Piece::Field(#field) },
+    });
+    let field_values = field_names.iter().enumerate().map(|(i, field)| {
+        let ident = Ident::new(field, Span::call_site());
+        quote! {
+            #ident: ::adventofcodelib::line::parse_field(input, #field, values[#i])?
+        }
+    });
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #type_generics #where_clause {
+            type Err = ::adventofcodelib::line::LineError;
+
+            fn from_str(input: &str) -> ::std::result::Result<Self, Self::Err> {
+                const PIECES: &[::adventofcodelib::line::Piece] = &[#(#piece_tokens),*];
+                let values = ::adventofcodelib::line::match_pattern(input, PIECES)?;
+                ::std::result::Result::Ok(#name {
+                    #(#field_values),*
+                })
+            }
+        }
+    })
+}
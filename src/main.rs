@@ -1,23 +1,761 @@
 extern crate adventofcodelib;
 
+mod tui;
+
+use adventofcodelib::cache::{AnswerCache, CacheKey, CachedAnswer};
+use adventofcodelib::config::{Config, OutputFormat};
+use adventofcodelib::runner::RunReport;
+use adventofcodelib::solutions::Solution;
 use adventofcodelib::*;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: alloc_stats::CountingAllocator = alloc_stats::CountingAllocator;
+
+const USAGE: &str = "usage:
+    adventofcode               run all solutions
+    adventofcode run [--year Y] [--day N] [--part P] [--timeout SECS] [--jobs J] [--no-cache]
+                     [--explain] [--variant naive|fast|parallel]
+                               run the solutions, each one is stopped after SECS seconds
+                               (0 for no limit), J solutions at the same time; the answers
+                               for unchanged inputs and solvers are taken from the cache;
+                               --explain tells how the answers were derived; --variant
+                               picks the implementation of the tasks solved in several ways
+    adventofcode verify [--year Y] [--day N] [--part P] [--timeout SECS]
+                               run the solutions without the cache and report the answers
+                               which differ from the cached ones
+    adventofcode bench [--year Y] [--day N] [--part P] [--iterations K] [--timeout SECS]
+                               time the solutions over K runs (10 by default), every
+                               variant of a task is timed
+    adventofcode show --day N  show the archived puzzle statement of the day
+    adventofcode examples --day N
+                               pick the examples of the saved puzzle page as test fixtures
+    adventofcode leaderboard FILE [--table times|scores|history] [--csv]
+                               analyze the private leaderboard JSON export
+    adventofcode tui           browse the calendar of the puzzles, run the solutions
+    adventofcode report [--year Y] [--timeout SECS] [--readme FILE]
+                               run the solutions and write the results table into the
+                               marked section of README.md
+    adventofcode render FILE [--input FILE]
+                               draw the day 3 claims into FILE: text (.txt) for small
+                               fabrics, a picture (.ppm or .png) with the overlaps
+                               highlighted and the intact claims outlined
+    adventofcode encrypt [--day N]
+                               write the encrypted copies (dayN_data.txt.enc) of the inputs
+    adventofcode decrypt [--day N]
+                               restore the inputs from their encrypted copies
+    adventofcode keygen        print a new input key
+    adventofcode config show   show the effective configuration
 
-// the path to the files with input data
-pub const FILE_NAME_DAY1: &str = "./input_data/day1_data.txt";
-pub const FILE_NAME_DAY2: &str = "./input_data/day2_data.txt";
-pub const FILE_NAME_DAY3: &str = "./input_data/day3_data.txt";
-pub const FILE_NAME_DAY4: &str = "./input_data/day4_data.txt";
-pub const FILE_NAME_DAY5: &str = "./input_data/day5_data.txt";
+The defaults come from aoc.toml (searched from the current directory upward, then in
+$XDG_CONFIG_HOME/aoc/) and the AOC_* environment variables.
+The input key is read from $AOC_INPUT_KEY, the file $AOC_INPUT_KEY_FILE or
+$XDG_CONFIG_HOME/aoc/input.key.";
 
 fn main() {
-    println!("day1 first task={}", day1_task1(FILE_NAME_DAY1));
-    println!("day1 second task={}", day1_task2(FILE_NAME_DAY1));
-    println!("day2 first task={}", day2_task1(FILE_NAME_DAY2));
-    println!("day2 second task={}", day2_task2(FILE_NAME_DAY2));
-    println!("day3 first task={}", day3_task1(FILE_NAME_DAY3));
-    println!("day3 second task={}", day3_task2(FILE_NAME_DAY3));
-    println!("day4 first task={}", day4_task1(FILE_NAME_DAY4));
-    println!("day4 second task={}", day4_task2(FILE_NAME_DAY4));
-    println!("day5 first task={}", day5_task1(FILE_NAME_DAY5));
-    println!("day5 second task={}", day5_task2(FILE_NAME_DAY5));
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let config = Config::load().unwrap_or_else(|e| fail(&e));
+    match args.first().map(String::as_str) {
+        None => run(&config, &[]),
+        Some("run") => run(&config, &args[1..]),
+        Some("bench") => bench(&config, &args[1..]),
+        Some("show") => show(&config, &args[1..]),
+        Some("examples") => examples(&config, &args[1..]),
+        Some("leaderboard") => leaderboard(&config, &args[1..]),
+        Some("verify") => verify(&config, &args[1..]),
+        Some("report") => report(&config, &args[1..]),
+        Some("render") => render(&config, &args[1..]),
+        Some("tui") => {
+            tui::run(&config).unwrap_or_else(|e| fail(&format!("Terminal error: {}", e)))
+        }
+        Some("encrypt") => crypt(&config, &args[1..], true),
+        Some("decrypt") => crypt(&config, &args[1..], false),
+        Some("keygen") => println!("{}", store::InputKey::generate().to_hex()),
+        Some("config") if args.get(1).map(String::as_str) == Some("show") => {
+            print!("{}", config.show())
+        }
+        Some(_) => fail(USAGE),
+    }
+}
+
+///
+/// Run the selected solutions and print the answers with the time they took.
+/// With the `alloc-stats` feature the heap usage is printed too; when several solutions
+/// run at the same time their allocations are mixed up.
+/// Exits with an error when a solution timed out or panicked.
+///
+fn run(config: &Config, args: &[String]) {
+    let limit = timeout_argument(config, args);
+    let jobs = match option_argument(args, "--jobs") {
+        Some(jobs) => jobs
+            .parse::<usize>()
+            .ok()
+            .filter(|&j| j > 0)
+            .unwrap_or_else(|| fail("--jobs expects a positive number")),
+        None => config.parallelism.value,
+    };
+    let solutions = selected_solutions(config, args);
+    let mut cache = if args.iter().any(|a| a == "--no-cache") {
+        None
+    } else {
+        Some(load_cache(config))
+    };
+    let keys = solutions
+        .iter()
+        .map(|solution| {
+            // the other variants are asked for to be run, not for their answers
+            cache
+                .as_ref()
+                .filter(|_| solution.variant == solutions::DEFAULT_VARIANT)
+                .and_then(|_| CacheKey::of(solution).ok())
+        })
+        .collect::<Vec<Option<CacheKey>>>();
+    let cached = keys
+        .iter()
+        .map(|key| {
+            let answer = cache.as_ref()?.get(key.as_ref()?)?;
+            Some(RunReport {
+                outcome: runner::Outcome::Solved(answer.answer.clone()),
+                elapsed: answer.elapsed,
+                alloc: None,
+            })
+        })
+        .collect::<Vec<Option<RunReport>>>();
+    let csv = config.output_format.value == OutputFormat::Csv;
+    if csv {
+        println!("year,day,part,answer,status,elapsed_ms,allocations,allocated,peak");
+    }
+    let explain = args.iter().any(|a| a == "--explain");
+    let mut failed = false;
+    let mut print = |solution: &Solution, report: &RunReport, is_cached: bool| {
+        failed |= report.answer().is_none();
+        if csv {
+            println!("{}", csv_line(solution, report, is_cached));
+            return;
+        }
+        let mut line = format!(
+            "{}={}  ({}",
+            solution.name(),
+            report.outcome,
+            runner::format_elapsed(report.elapsed)
+        );
+        if let Some(alloc) = report.alloc {
+            line.push_str(&format!(", {}", alloc));
+        }
+        if is_cached {
+            line.push_str(", cached");
+        }
+        println!("{})", line);
+        if let (true, Some(explain), Some(_)) = (explain, solution.explain, report.answer()) {
+            for line in explain(&solution.file_name).lines() {
+                println!("    {}", line);
+            }
+        }
+    };
+    let mut remember = |key: &Option<CacheKey>, report: &RunReport| {
+        if let (Some(cache), Some(key), Some(answer)) = (cache.as_mut(), key, report.answer()) {
+            cache.insert(
+                key.clone(),
+                CachedAnswer {
+                    answer: answer.to_string(),
+                    elapsed: report.elapsed,
+                },
+            );
+        }
+    };
+    if jobs > 1 {
+        let pending = solutions
+            .iter()
+            .zip(&cached)
+            .filter(|(_, cached)| cached.is_none())
+            .map(|(solution, _)| solution.clone())
+            .collect::<Vec<Solution>>();
+        let mut fresh = run_parallel(&pending, limit, jobs).into_iter();
+        for (i, solution) in solutions.iter().enumerate() {
+            match &cached[i] {
+                Some(report) => print(solution, report, true),
+                None => {
+                    let report = fresh.next().expect("RUNNER: a solution was not run!");
+                    remember(&keys[i], &report);
+                    print(solution, &report, false);
+                }
+            }
+        }
+    } else {
+        let show_progress = io::stderr().is_terminal();
+        for (i, solution) in solutions.iter().enumerate() {
+            if let Some(report) = &cached[i] {
+                print(solution, report, true);
+                continue;
+            }
+            let reporter = if show_progress {
+                Some(progress_line(solution.name()))
+            } else {
+                None
+            };
+            let report = runner::run_with_progress(solution, limit, reporter);
+            if show_progress {
+                eprint!("\r\x1b[2K");
+            }
+            remember(&keys[i], &report);
+            print(solution, &report, false);
+        }
+    }
+    if let Some(cache) = cache {
+        save_cache(config, &cache);
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+///
+/// Run the selected solutions without the cache and compare the answers with the cached
+/// answers for the same input, so a change of a solver answer is noticed.
+/// Exits with an error when an answer changed or a solution failed.
+///
+fn verify(config: &Config, args: &[String]) {
+    let limit = timeout_argument(config, args);
+    let mut cache = load_cache(config);
+    let mut failed = false;
+    for solution in selected_solutions(config, args) {
+        let key = match CacheKey::of(&solution) {
+            Ok(key) => key,
+            Err(e) => {
+                println!("{}: skipped, {}", solution.name(), e);
+                continue;
+            }
+        };
+        let report = runner::run(&solution, limit);
+        let answer = match report.answer() {
+            Some(answer) => answer.to_string(),
+            None => {
+                println!("{}: {}", solution.name(), report.outcome);
+                failed = true;
+                continue;
+            }
+        };
+        let mut previous = cache
+            .other_versions(&key)
+            .map(|(_, cached)| cached.answer.clone())
+            .collect::<Vec<String>>();
+        previous.extend(cache.get(&key).map(|cached| cached.answer.clone()));
+        previous.sort();
+        previous.dedup();
+        if previous.is_empty() {
+            println!("{}: new answer {}", solution.name(), answer);
+        } else if previous.iter().all(|p| *p == answer) {
+            println!("{}: ok {}", solution.name(), answer);
+        } else {
+            println!(
+                "{}: CHANGED {}, cached {}",
+                solution.name(),
+                answer,
+                previous
+                    .iter()
+                    .filter(|p| **p != answer)
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            );
+            failed = true;
+        }
+        cache.insert(
+            key,
+            CachedAnswer {
+                answer,
+                elapsed: report.elapsed,
+            },
+        );
+    }
+    save_cache(config, &cache);
+    if failed {
+        process::exit(1);
+    }
+}
+
+fn load_cache(config: &Config) -> AnswerCache {
+    AnswerCache::load(&config.cache_file.value).unwrap_or_else(|e| {
+        fail(&format!(
+            "Cannot read the answer cache '{}': {}",
+            config.cache_file.value.display(),
+            e
+        ))
+    })
+}
+
+fn save_cache(config: &Config, cache: &AnswerCache) {
+    if let Err(e) = cache.save() {
+        eprintln!(
+            "Cannot write the answer cache '{}': {}",
+            config.cache_file.value.display(),
+            e
+        );
+    }
+}
+
+///
+/// Run the solutions on `jobs` threads. Returns the reports in the order of the solutions.
+///
+fn run_parallel(solutions: &[Solution], limit: Option<Duration>, jobs: usize) -> Vec<RunReport> {
+    let next = AtomicUsize::new(0);
+    let reports = Mutex::new(vec![None; solutions.len()]);
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(solutions.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= solutions.len() {
+                    break;
+                }
+                let report = runner::run(&solutions[i], limit);
+                reports.lock().expect("Reports lock is poisoned!")[i] = Some(report);
+            });
+        }
+    });
+    reports
+        .into_inner()
+        .expect("Reports lock is poisoned!")
+        .into_iter()
+        .map(|report| report.expect("RUNNER: a solution was not run!"))
+        .collect()
+}
+
+fn csv_line(solution: &Solution, report: &RunReport, is_cached: bool) -> String {
+    let (answer, status) = match &report.outcome {
+        runner::Outcome::Solved(answer) if is_cached => (answer.clone(), String::from("cached")),
+        runner::Outcome::Solved(answer) => (answer.clone(), String::from("solved")),
+        runner::Outcome::TimedOut(_) => (String::new(), String::from("timed out")),
+        runner::Outcome::Panicked(_) => (String::new(), String::from("panicked")),
+    };
+    let alloc = match report.alloc {
+        Some(alloc) => format!("{},{},{}", alloc.allocations, alloc.allocated, alloc.peak),
+        None => String::from(",,"),
+    };
+    format!(
+        "{},{},{},{},{},{:.3},{}",
+        solution.year,
+        solution.day,
+        solution.part,
+        answer,
+        status,
+        report.elapsed.as_secs_f64() * 1000.0,
+        alloc
+    )
+}
+
+///
+/// A reporter which keeps the progress of the solution on the last line of stderr.
+///
+fn progress_line(name: String) -> Arc<dyn progress::ProgressReporter> {
+    const REFRESH: Duration = Duration::from_millis(100);
+    let last_update = Mutex::new(Instant::now());
+    Arc::new(move |phase: &str, done: usize, total: usize| {
+        let mut last_update = last_update.lock().expect("Progress lock is poisoned!");
+        if last_update.elapsed() < REFRESH {
+            return;
+        }
+        *last_update = Instant::now();
+        let percent = (done * 100).checked_div(total).unwrap_or(100);
+        eprint!(
+            "\r\x1b[2K{}: {} {}/{} ({}%)",
+            name, phase, done, total, percent
+        );
+    })
+}
+
+///
+/// Run the selected solutions several times and print the timings.
+///
+fn bench(config: &Config, args: &[String]) {
+    let iterations = match option_argument(args, "--iterations") {
+        Some(iterations) => iterations
+            .parse::<u32>()
+            .ok()
+            .filter(|&i| i > 0)
+            .unwrap_or_else(|| fail("--iterations expects a positive number")),
+        None => 10,
+    };
+    let limit = timeout_argument(config, args);
+    let mut failed = false;
+    for solution in selected_variants(config, args) {
+        let report = match runner::bench(&solution, iterations, limit) {
+            Ok(report) => report,
+            Err(report) => {
+                failed = true;
+                println!("{}: {}", solution.name(), report.outcome);
+                continue;
+            }
+        };
+        let mut line = format!(
+            "{}: min {}, mean {}, max {} over {} runs",
+            solution.name(),
+            runner::format_elapsed(report.min),
+            runner::format_elapsed(report.mean),
+            runner::format_elapsed(report.max),
+            report.iterations
+        );
+        if let Some(alloc) = report.alloc {
+            line.push_str(&format!("; {} per run", alloc));
+        }
+        println!("{}", line);
+    }
+    if failed {
+        process::exit(1);
+    }
+}
+
+///
+/// Get the time limit of a single run from the `--timeout SECS` argument,
+/// the configured one by default.
+///
+fn timeout_argument(config: &Config, args: &[String]) -> Option<Duration> {
+    match option_argument(args, "--timeout") {
+        Some(seconds) => match seconds.parse::<u64>() {
+            Ok(0) => None,
+            Ok(seconds) => Some(Duration::from_secs(seconds)),
+            Err(_) => fail("--timeout expects a number of seconds"),
+        },
+        None => config.timeout.value,
+    }
+}
+
+///
+/// The solutions chosen by the `--year Y`, `--day N`, `--part P` and `--variant NAME`
+/// arguments: the default variant of the tasks without the named one.
+///
+fn selected_solutions(config: &Config, args: &[String]) -> Vec<Solution> {
+    let variants = selected_variants(config, args);
+    let variant = option_argument(args, "--variant").unwrap_or(solutions::DEFAULT_VARIANT);
+    if variant.starts_with("--") {
+        fail("--variant expects a name");
+    }
+    if !variants.iter().any(|s| s.variant == variant) {
+        fail(&format!(
+            "There is no '{}' variant of the selected solutions",
+            variant
+        ));
+    }
+    let mut selected: Vec<Solution> = Vec::new();
+    for solution in variants {
+        let task = |s: &Solution| (s.year, s.day, s.part);
+        match selected.last_mut() {
+            Some(last) if task(last) == task(&solution) => {
+                if solution.variant == variant {
+                    *last = solution;
+                }
+            }
+            _ => selected.push(solution),
+        }
+    }
+    selected
+}
+
+///
+/// All variants of the solutions chosen by the `--year Y`, `--day N` and `--part P`
+/// arguments: all the solutions of the default year by default.
+///
+fn selected_variants(config: &Config, args: &[String]) -> Vec<Solution> {
+    let year = match option_argument(args, "--year") {
+        Some(year) => year
+            .parse::<u16>()
+            .unwrap_or_else(|_| fail("--year expects a number")),
+        None => config.default_year.value,
+    };
+    let day = option_argument(args, "--day").map(|_| day_argument(args));
+    let part = option_argument(args, "--part").map(|part| match part {
+        "1" => 1,
+        "2" => 2,
+        _ => fail("--part expects 1 or 2"),
+    });
+    let selected = solutions::variants_in(&config.input_root())
+        .into_iter()
+        .filter(|s| s.year == year)
+        .filter(|s| day.is_none() || day == Some(s.day))
+        .filter(|s| part.is_none() || part == Some(s.part))
+        .collect::<Vec<Solution>>();
+    if selected.is_empty() {
+        fail("There is no solution for the selected year, day and part");
+    }
+    check_encrypted_inputs(&selected);
+    selected
+}
+
+///
+/// Exit with an error when an encrypted input of the solutions cannot be decrypted,
+/// instead of letting every solver panic on it.
+///
+fn check_encrypted_inputs(solutions: &[Solution]) {
+    let mut checked: Vec<&str> = Vec::new();
+    for solution in solutions {
+        let file_name = solution.file_name.as_str();
+        if checked.contains(&file_name) || Path::new(file_name).is_file() {
+            continue;
+        }
+        checked.push(file_name);
+        if let Err(e) = store::read_encrypted(file_name) {
+            fail(&format!(
+                "Cannot read the input of day {}: {}",
+                solution.day, e
+            ));
+        }
+    }
+}
+
+///
+/// Print the puzzle statement of the day. The saved puzzle page is converted to Markdown
+/// on the first call.
+///
+fn show(config: &Config, args: &[String]) {
+    let day = day_argument(args);
+    let input_root = config.input_root();
+    match puzzle::load_puzzle(&input_root, day) {
+        Ok(markdown) => print!(
+            "{}",
+            puzzle::render_markdown(&markdown, io::stdout().is_terminal())
+        ),
+        Err(e) => fail(&format!(
+            "Cannot load the puzzle of day {}: {}. Save the puzzle page to '{}'.",
+            day,
+            e,
+            puzzle::puzzle_html_file_name(&input_root, day)
+        )),
+    }
+}
+
+///
+/// Propose the examples found in the saved puzzle page and store the confirmed ones
+/// as fixtures for the example tests.
+///
+fn examples(config: &Config, args: &[String]) {
+    let day = day_argument(args);
+    let input_root = config.input_root();
+    let page_file_name = puzzle::puzzle_html_file_name(&input_root, day);
+    let html = fs::read_to_string(&page_file_name).unwrap_or_else(|e| {
+        fail(&format!(
+            "Cannot read the puzzle page '{}': {}",
+            page_file_name, e
+        ))
+    });
+    let candidates = examples::find_examples(&html);
+    if candidates.is_empty() {
+        println!("No examples found in the puzzle of day {}.", day);
+        return;
+    }
+    println!(
+        "Enter the answer to save the example ('-' to skip, 'q' to stop, empty to accept the proposed one)."
+    );
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    for (i, candidate) in candidates.iter().enumerate() {
+        println!(
+            "\nexample {}/{} (part {}):",
+            i + 1,
+            candidates.len(),
+            candidate.part
+        );
+        for line in candidate.input.lines() {
+            println!("    {}", line);
+        }
+        print!("answer [{}]> ", candidate.answer.as_deref().unwrap_or(""));
+        io::stdout().flush().expect("Cannot write to stdout!");
+        let reply = match lines.next() {
+            Some(line) => line.expect("Cannot read from stdin!"),
+            None => break,
+        };
+        let answer = match reply.trim() {
+            "q" => break,
+            "-" => continue,
+            "" => match &candidate.answer {
+                Some(answer) => answer.clone(),
+                None => continue,
+            },
+            answer => answer.to_string(),
+        };
+        let mut example = candidate.clone();
+        example.answer = Some(answer);
+        match examples::save_fixture(&input_root, day, &example) {
+            Ok(fixture) => println!(
+                "saved to '{}' (part {} answer {})",
+                fixture.file_name, fixture.part, fixture.answer
+            ),
+            Err(e) => fail(&format!("Cannot save the example: {}", e)),
+        }
+    }
+}
+
+///
+/// Print the solve times, the recomputed local scores and the ranking history of the
+/// private leaderboard export.
+///
+fn leaderboard(config: &Config, args: &[String]) {
+    let file_name = match args.first() {
+        Some(file_name) if !file_name.starts_with("--") => file_name,
+        _ => fail(USAGE),
+    };
+    let board = leaderboard::load_leaderboard(file_name).unwrap_or_else(|e| {
+        fail(&format!(
+            "Cannot load the leaderboard '{}': {}",
+            file_name, e
+        ))
+    });
+    let csv = args.iter().any(|a| a == "--csv") || config.output_format.value == OutputFormat::Csv;
+    let tables = match option_argument(args, "--table") {
+        None => vec!["times", "scores", "history"],
+        Some(table @ "times") | Some(table @ "scores") | Some(table @ "history") => vec![table],
+        Some(_) => fail("--table expects one of: times, scores, history"),
+    };
+    for (i, &name) in tables.iter().enumerate() {
+        let (title, table) = match name {
+            "times" => ("solve times", leaderboard::solve_times_table(&board)),
+            "scores" => ("local scores", leaderboard::local_scores_table(&board)),
+            _ => (
+                "ranking history",
+                leaderboard::ranking_history_table(&board),
+            ),
+        };
+        if i > 0 {
+            println!();
+        }
+        if csv {
+            print!("{}", table.to_csv());
+        } else {
+            println!("{}", title);
+            print!("{}", table.to_text());
+        }
+    }
+}
+
+///
+/// Run the solutions of the year and rewrite the results section of the README.
+///
+fn report(config: &Config, args: &[String]) {
+    let limit = timeout_argument(config, args);
+    let readme = option_argument(args, "--readme").unwrap_or("README.md");
+    let solutions = selected_solutions(config, args);
+    let reports = run_parallel(&solutions, limit, config.parallelism.value);
+    let days = report::day_reports(&solutions, &reports, &config.input_root());
+    let document = match fs::read_to_string(readme) {
+        Ok(document) => document,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => fail(&format!("Cannot read '{}': {}", readme, e)),
+    };
+    let document = report::replace_section(&document, &report::markdown_table(&days))
+        .unwrap_or_else(|e| fail(&format!("Cannot update '{}': {}", readme, e)));
+    fs::write(readme, document)
+        .unwrap_or_else(|e| fail(&format!("Cannot write '{}': {}", readme, e)));
+    let stars = days.iter().map(|day| day.stars as usize).sum::<usize>();
+    println!(
+        "'{}' is updated: {} days, {} stars",
+        readme,
+        days.len(),
+        stars
+    );
+}
+
+///
+/// Draw the day 3 claims of the input (the `--input FILE` argument or the configured input)
+/// into the file.
+///
+fn render(config: &Config, args: &[String]) {
+    let output = match args.first() {
+        Some(output) if !output.starts_with("--") => output,
+        _ => fail(USAGE),
+    };
+    let input = option_argument(args, "--input")
+        .map(String::from)
+        .unwrap_or_else(|| input_file_name(&config.input_root(), 3));
+    if let Err(e) = input_content(&input) {
+        fail(&e);
+    }
+    let fabric = fabric::Fabric::new(&parse_inches(&input));
+    render::write(&fabric, Path::new(output)).unwrap_or_else(|e| fail(&e));
+    let (_, _, width, height) = fabric.bounds();
+    println!(
+        "'{}' is written: {} claims on {}×{} square inches",
+        output,
+        fabric.claims().len(),
+        width,
+        height
+    );
+}
+
+///
+/// Encrypt the inputs of the `--day N` argument (all of them by default) or restore them
+/// from the encrypted copies.
+///
+fn crypt(config: &Config, args: &[String], encrypt: bool) {
+    let key = match store::InputKey::from_env() {
+        Ok(Some(key)) => key,
+        Ok(None) => fail(&format!(
+            "There is no input key: set ${} or ${}, 'adventofcode keygen' makes a new one",
+            store::KEY_ENV,
+            store::KEY_FILE_ENV
+        )),
+        Err(e) => fail(&e),
+    };
+    let days = match option_argument(args, "--day") {
+        Some(_) => vec![day_argument(args)],
+        None => (1..=25).collect(),
+    };
+    let input_root = config.input_root();
+    for day in days {
+        let file_name = adventofcodelib::input_file_name(&input_root, day);
+        let result = if encrypt {
+            if !Path::new(&file_name).is_file() {
+                continue;
+            }
+            store::encrypt_file(&key, &file_name)
+                .map(|encrypted| format!("{} -> {}", file_name, encrypted))
+        } else {
+            let encrypted = store::encrypted_file_name(&file_name);
+            if !Path::new(&encrypted).is_file() {
+                continue;
+            }
+            store::decrypt_file(&key, &file_name).map(|_| format!("{} -> {}", encrypted, file_name))
+        };
+        match result {
+            Ok(message) => println!("{}", message),
+            Err(e) => fail(&format!("Cannot process the input of day {}: {}", day, e)),
+        }
+    }
+}
+
+///
+/// Get the value of the `NAME VALUE` argument.
+///
+fn option_argument<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == name)
+        .map(|i| match args.get(i + 1) {
+            Some(value) => value.as_str(),
+            None => fail(&format!("{} expects a value", name)),
+        })
+}
+
+///
+/// Get the value of the `--day N` argument.
+///
+fn day_argument(args: &[String]) -> u8 {
+    match option_argument(args, "--day") {
+        Some(day) => day
+            .parse::<u8>()
+            .ok()
+            .filter(|day| (1..=25).contains(day))
+            .unwrap_or_else(|| fail("--day expects a number from 1 to 25")),
+        None => fail(USAGE),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(2)
 }
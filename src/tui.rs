@@ -0,0 +1,447 @@
+use adventofcodelib::cache::{AnswerCache, CacheKey, CachedAnswer};
+use adventofcodelib::config::Config;
+use adventofcodelib::runner::{self, RunReport};
+use adventofcodelib::solutions::{self, Solution};
+use adventofcodelib::{input_file_name, input_stats, puzzle};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::io;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DAYS: u8 = 25;
+const CALENDAR_COLUMNS: u8 = 5;
+const CALENDAR_WIDTH: u16 = 5 * 7 + 2;
+// the run output lines kept on the screen
+const OUTPUT_LINES: usize = 50;
+
+///
+/// What the running solution thread tells the UI
+///
+enum RunMessage {
+    Progress(String, usize, usize),
+    Finished(RunReport),
+}
+
+struct Running {
+    solution: Solution,
+    started: Instant,
+    progress: Option<(String, usize, usize)>,
+    receiver: Receiver<RunMessage>,
+}
+
+///
+/// What is shown about the selected day, read when the day is selected
+///
+#[derive(Default)]
+struct DayView {
+    input: Option<String>,
+    puzzle: String,
+}
+
+struct App {
+    config: Config,
+    solutions: Vec<Solution>,
+    /// the cache keys of the solutions by (year, day, part), when their inputs can be read
+    keys: HashMap<(u16, u8, u8), CacheKey>,
+    years: Vec<u16>,
+    year: usize,
+    day: u8,
+    view: DayView,
+    cache: AnswerCache,
+    puzzle_scroll: u16,
+    output: Vec<String>,
+    running: Option<Running>,
+    quit: bool,
+}
+
+///
+/// Browse the calendar of the solved puzzles in the terminal. Blocks until the user quits.
+///
+pub fn run(config: &Config) -> io::Result<()> {
+    let solutions = solutions::solutions_in(&config.input_root());
+    let mut years = solutions.iter().map(|s| s.year).collect::<Vec<u16>>();
+    years.dedup();
+    let year = years
+        .iter()
+        .position(|&y| y == config.default_year.value)
+        .unwrap_or(0);
+    let cache = AnswerCache::load(&config.cache_file.value)?;
+    let keys = solutions
+        .iter()
+        .filter_map(|s| Some(((s.year, s.day, s.part), CacheKey::of(s).ok()?)))
+        .collect();
+    let mut app = App {
+        config: config.clone(),
+        solutions,
+        keys,
+        years,
+        year,
+        day: 1,
+        view: DayView::default(),
+        cache,
+        puzzle_scroll: 0,
+        output: Vec::new(),
+        running: None,
+        quit: false,
+    };
+    app.refresh_view();
+    let mut terminal = ratatui::init();
+    let result = app.event_loop(&mut terminal);
+    ratatui::restore();
+    if let Err(e) = app.cache.save() {
+        eprintln!(
+            "Cannot write the answer cache '{}': {}",
+            config.cache_file.value.display(),
+            e
+        );
+    }
+    result
+}
+
+impl App {
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            self.receive();
+            terminal.draw(|frame| self.draw(frame))?;
+            if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.handle_key(key.code);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Left | KeyCode::Char('h') => self.select_day(self.day.saturating_sub(1)),
+            KeyCode::Right | KeyCode::Char('l') => self.select_day(self.day + 1),
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.select_day(self.day.saturating_sub(CALENDAR_COLUMNS))
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.select_day(self.day + CALENDAR_COLUMNS),
+            KeyCode::Tab | KeyCode::Char(']') => {
+                self.year = (self.year + 1) % self.years.len().max(1);
+                self.refresh_view();
+            }
+            KeyCode::BackTab | KeyCode::Char('[') => {
+                self.year = (self.year + self.years.len().max(1) - 1) % self.years.len().max(1);
+                self.refresh_view();
+            }
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                self.puzzle_scroll = self.puzzle_scroll.saturating_add(10)
+            }
+            KeyCode::PageUp => self.puzzle_scroll = self.puzzle_scroll.saturating_sub(10),
+            KeyCode::Char('1') => self.start(1),
+            KeyCode::Char('2') => self.start(2),
+            _ => {}
+        }
+    }
+
+    fn select_day(&mut self, day: u8) {
+        if (1..=DAYS).contains(&day) && day != self.day {
+            self.day = day;
+            self.refresh_view();
+        }
+    }
+
+    ///
+    /// Read the input stats and the puzzle statement of the selected day.
+    ///
+    fn refresh_view(&mut self) {
+        let input_root = self.config.input_root();
+        let input = input_file_name(&input_root, self.day);
+        self.view = DayView {
+            input: input_stats(&input).ok().map(|stats| {
+                format!(
+                    "input: {} lines, {} bytes ({})",
+                    stats.lines, stats.bytes, input
+                )
+            }),
+            puzzle: match puzzle::load_puzzle(&input_root, self.day) {
+                Ok(markdown) => puzzle::render_markdown(&markdown, false),
+                Err(_) => format!(
+                    "The puzzle is not archived. Save the page to '{}' to read it here.",
+                    puzzle::puzzle_html_file_name(&input_root, self.day)
+                ),
+            },
+        };
+        self.puzzle_scroll = 0;
+    }
+
+    fn year(&self) -> u16 {
+        self.years
+            .get(self.year)
+            .copied()
+            .unwrap_or(self.config.default_year.value)
+    }
+
+    fn solution(&self, day: u8, part: u8) -> Option<&Solution> {
+        let year = self.year();
+        self.solutions
+            .iter()
+            .find(|s| s.year == year && s.day == day && s.part == part)
+    }
+
+    ///
+    /// The cached answer of the part for the current input and solver
+    ///
+    fn last_answer(&self, day: u8, part: u8) -> Option<&CachedAnswer> {
+        self.cache.get(self.keys.get(&(self.year(), day, part))?)
+    }
+
+    ///
+    /// Run the part of the selected day in the background.
+    ///
+    fn start(&mut self, part: u8) {
+        if self.running.is_some() {
+            self.output
+                .push(String::from("wait for the running solution to finish"));
+            return;
+        }
+        let solution = match self.solution(self.day, part) {
+            Some(solution) => solution.clone(),
+            None => {
+                self.output
+                    .push(format!("day{} part {} is not solved", self.day, part));
+                return;
+            }
+        };
+        self.output.push(format!("running {}...", solution.name()));
+        let (sender, receiver) = mpsc::channel();
+        let progress = sender.clone();
+        let reporter = Arc::new(move |phase: &str, done: usize, total: usize| {
+            // the UI may be closed already
+            let _ = progress.send(RunMessage::Progress(phase.to_string(), done, total));
+        });
+        let limit = self.config.timeout.value;
+        let thread_solution = solution.clone();
+        thread::spawn(move || {
+            let report = runner::run_with_progress(&thread_solution, limit, Some(reporter));
+            let _ = sender.send(RunMessage::Finished(report));
+        });
+        self.running = Some(Running {
+            solution,
+            started: Instant::now(),
+            progress: None,
+            receiver,
+        });
+    }
+
+    ///
+    /// Take the messages of the running solution.
+    ///
+    fn receive(&mut self) {
+        let running = match self.running.as_mut() {
+            Some(running) => running,
+            None => return,
+        };
+        loop {
+            match running.receiver.try_recv() {
+                Ok(RunMessage::Progress(phase, done, total)) => {
+                    running.progress = Some((phase, done, total))
+                }
+                Ok(RunMessage::Finished(report)) => {
+                    let solution = running.solution.clone();
+                    self.running = None;
+                    self.finish(&solution, report);
+                    return;
+                }
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => {
+                    self.output
+                        .push(String::from("the solution thread is lost"));
+                    self.running = None;
+                    return;
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self, solution: &Solution, report: RunReport) {
+        let mut line = format!(
+            "{}={}  ({}",
+            solution.name(),
+            report.outcome,
+            runner::format_elapsed(report.elapsed)
+        );
+        if let Some(alloc) = report.alloc {
+            line.push_str(&format!(", {}", alloc));
+        }
+        self.output.push(format!("{})", line));
+        let key = self.keys.get(&(solution.year, solution.day, solution.part));
+        if let (Some(answer), Some(key)) = (report.answer(), key) {
+            self.cache.insert(
+                key.clone(),
+                CachedAnswer {
+                    answer: answer.to_string(),
+                    elapsed: report.elapsed,
+                },
+            );
+        }
+        let excess = self.output.len().saturating_sub(OUTPUT_LINES);
+        self.output.drain(..excess);
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [title, body, help] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let years = self
+            .years
+            .iter()
+            .enumerate()
+            .flat_map(|(i, year)| {
+                let style = if i == self.year {
+                    Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::new().fg(Color::DarkGray)
+                };
+                vec![Span::styled(year.to_string(), style), Span::raw(" ")]
+            })
+            .collect::<Vec<Span>>();
+        let mut title_line = vec![Span::styled(
+            " Advent of Code  ",
+            Style::new().fg(Color::Green).add_modifier(Modifier::BOLD),
+        )];
+        title_line.extend(years);
+        frame.render_widget(Line::from(title_line), title);
+        frame.render_widget(
+            Line::styled(
+                " arrows/hjkl day  tab/[] year  1/2 run part  pgup/pgdn scroll  q quit",
+                Style::new().fg(Color::DarkGray),
+            ),
+            help,
+        );
+
+        let [calendar, details] =
+            Layout::horizontal([Constraint::Length(CALENDAR_WIDTH), Constraint::Min(0)])
+                .areas(body);
+        self.draw_calendar(frame, calendar);
+        let [info, output, statement] = Layout::vertical([
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Min(0),
+        ])
+        .areas(details);
+        self.draw_info(frame, info);
+        self.draw_output(frame, output);
+        self.draw_puzzle(frame, statement);
+    }
+
+    fn draw_calendar(&self, frame: &mut Frame, area: Rect) {
+        let mut lines = vec![Line::raw("")];
+        for row in 0..DAYS / CALENDAR_COLUMNS {
+            let mut spans = Vec::new();
+            for column in 1..=CALENDAR_COLUMNS {
+                let day = row * CALENDAR_COLUMNS + column;
+                let solved = (1..=2)
+                    .filter(|&part| self.solution(day, part).is_some())
+                    .count();
+                let stars = (1..=2)
+                    .filter(|&part| self.last_answer(day, part).is_some())
+                    .count();
+                let mut style = if solved == 0 {
+                    Style::new().fg(Color::DarkGray)
+                } else {
+                    Style::new()
+                };
+                if day == self.day {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                spans.push(Span::styled(format!(" {:>2}", day), style));
+                spans.push(Span::styled(
+                    format!("{:<2}", "*".repeat(stars)),
+                    Style::new().fg(Color::Yellow),
+                ));
+                spans.push(Span::raw("  "));
+            }
+            lines.push(Line::from(spans));
+            lines.push(Line::raw(""));
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(format!(" {} ", self.year()))),
+            area,
+        );
+    }
+
+    fn draw_info(&self, frame: &mut Frame, area: Rect) {
+        let mut lines = vec![match &self.view.input {
+            Some(input) => Line::raw(input.as_str()),
+            None => Line::styled("input: not available", Style::new().fg(Color::DarkGray)),
+        }];
+        for part in 1..=2 {
+            let text = match (
+                self.solution(self.day, part),
+                self.last_answer(self.day, part),
+            ) {
+                (None, _) => String::from("not solved"),
+                (Some(_), None) => String::from("no answer yet, press the part number to run"),
+                (Some(_), Some(cached)) => format!(
+                    "{}  ({})",
+                    cached.answer,
+                    runner::format_elapsed(cached.elapsed)
+                ),
+            };
+            lines.push(Line::raw(format!("part {}: {}", part, text)));
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(format!(" day {} ", self.day))),
+            area,
+        );
+    }
+
+    fn draw_output(&self, frame: &mut Frame, area: Rect) {
+        let height = area.height.saturating_sub(2) as usize;
+        let mut lines = self
+            .output
+            .iter()
+            .map(|line| Line::raw(line.as_str()))
+            .collect::<Vec<Line>>();
+        if let Some(running) = &self.running {
+            let elapsed = runner::format_elapsed(running.started.elapsed());
+            let status = match &running.progress {
+                Some((phase, done, total)) => format!(
+                    "{}: {} {}/{} ({}%), {}",
+                    running.solution.name(),
+                    phase,
+                    done,
+                    total,
+                    (done * 100).checked_div(*total).unwrap_or(100),
+                    elapsed
+                ),
+                None => format!("{}: {}", running.solution.name(), elapsed),
+            };
+            lines.push(Line::styled(status, Style::new().fg(Color::Cyan)));
+        }
+        let skip = lines.len().saturating_sub(height);
+        frame.render_widget(
+            Paragraph::new(lines.split_off(skip)).block(Block::bordered().title(" output ")),
+            area,
+        );
+    }
+
+    fn draw_puzzle(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(
+            Paragraph::new(self.view.puzzle.as_str())
+                .wrap(Wrap { trim: false })
+                .scroll((self.puzzle_scroll, 0))
+                .block(Block::bordered().title(" puzzle ")),
+            area,
+        );
+    }
+}
@@ -1,51 +1,42 @@
 extern crate adventofcodelib;
 
+#[global_allocator]
+static ALLOCATOR: adventofcodelib::alloc_stats::CountingAllocator =
+    adventofcodelib::alloc_stats::CountingAllocator;
+
 #[test]
 fn check_day1_task1() {
-    assert_eq!(
-        520,
-        adventofcodelib::day1_task1(adventofcodelib::FILE_NAME_DAY1)
-    );
+    check_answer("520", 1, 1, adventofcodelib::FILE_NAME_DAY1);
 }
 
 #[test]
 fn check_day1_task2() {
-    assert_eq!(
-        394,
-        adventofcodelib::day1_task2(adventofcodelib::FILE_NAME_DAY1)
-    );
+    check_answer("394", 1, 2, adventofcodelib::FILE_NAME_DAY1);
 }
 
 #[test]
 fn check_day2_task1() {
-    assert_eq!(
-        7904,
-        adventofcodelib::day2_task1(adventofcodelib::FILE_NAME_DAY2)
-    );
+    check_answer("7904", 2, 1, adventofcodelib::FILE_NAME_DAY2);
 }
 
 #[test]
 fn check_day2_task2() {
-    assert_eq!(
+    check_answer(
         "wugbihckpoymcpaxefotvdzns",
-        adventofcodelib::day2_task2(adventofcodelib::FILE_NAME_DAY2)
+        2,
+        2,
+        adventofcodelib::FILE_NAME_DAY2,
     );
 }
 
 #[test]
 fn check_day3_task1() {
-    assert_eq!(
-        113966,
-        adventofcodelib::day3_task1(adventofcodelib::FILE_NAME_DAY3)
-    );
+    check_answer("113966", 3, 1, adventofcodelib::FILE_NAME_DAY3);
 }
 
 #[test]
 fn check_day3_task2() {
-    assert_eq!(
-        235,
-        adventofcodelib::day3_task2(adventofcodelib::FILE_NAME_DAY3)
-    );
+    check_answer("235", 3, 2, adventofcodelib::FILE_NAME_DAY3);
 }
 
 #[test]
@@ -56,6 +47,14 @@ fn check_parse_inch_valid() {
     assert_eq!(expected, inch);
 }
 
+#[test]
+fn check_parse_inch_without_spaces() {
+    let inch = adventofcodelib::parse_inch("#1 @ 1,3:4x4");
+    assert_eq!(adventofcodelib::Inch::new(1, 1, 3, 4, 4), inch);
+    let inch = adventofcodelib::parse_inch("#1@1,3:4x4");
+    assert_eq!(adventofcodelib::Inch::new(1, 1, 3, 4, 4), inch);
+}
+
 #[test]
 fn check_parse_inch_invalid() {
     let input = "#1318 @ 428,284: 25x21";
@@ -105,3 +104,1276 @@ fn check_parse_inch_must_fail_toll() {
     let input = "#1 @ 42,11: 55x4294967500";
     adventofcodelib::parse_inch(input);
 }
+
+const PUZZLE_PAGE: &str = r#"<!DOCTYPE html><html><body><main>
+<article class="day-desc"><h2>--- Day 1: Chronal Calibration ---</h2>
+<p>For example, if the device displays frequency changes of <code>+1, -2, +3, +1</code>:</p>
+<ul><li>Current frequency <code>0</code>, change of <code>+1</code>.</li>
+<li><code>+1, +1, -2</code> results in <code> 0</code></li></ul>
+<p>Starting with a frequency of zero, what is the <em>resulting frequency</em> &amp; 4 * 3?</p>
+</article>
+<p>Your puzzle answer was <code>520</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<pre><code>abcde
+fghij
+</code></pre>
+<p>See <a href="/2018/day/1/input">the input</a>.</p>
+</article>
+</main></body></html>"#;
+
+#[test]
+fn check_puzzle_extract_parts() {
+    let parts = adventofcodelib::puzzle::extract_parts(PUZZLE_PAGE);
+    assert_eq!(2, parts.len());
+    assert!(parts[0].starts_with("<h2>--- Day 1: Chronal Calibration ---</h2>"));
+    assert!(parts[1].starts_with("<h2 id=\"part2\">--- Part Two ---</h2>"));
+}
+
+#[test]
+fn check_puzzle_to_markdown() {
+    let markdown = adventofcodelib::puzzle::puzzle_to_markdown(PUZZLE_PAGE);
+    let expected = "## --- Day 1: Chronal Calibration ---
+
+For example, if the device displays frequency changes of `+1, -2, +3, +1`:
+
+- Current frequency `0`, change of `+1`.
+- `+1, +1, -2` results in ` 0`
+
+Starting with a frequency of zero, what is the *resulting frequency* & 4 \\* 3?
+
+## --- Part Two ---
+
+```
+abcde
+fghij
+```
+
+See [the input](/2018/day/1/input).
+";
+    assert_eq!(expected, markdown);
+}
+
+#[test]
+fn check_puzzle_render_markdown() {
+    let markdown = adventofcodelib::puzzle::puzzle_to_markdown(PUZZLE_PAGE);
+    let rendered = adventofcodelib::puzzle::render_markdown(&markdown, false);
+    assert!(rendered.starts_with("--- Day 1: Chronal Calibration ---\n\nFor example,"));
+    assert!(rendered.contains("  - Current frequency 0, change of +1.\n"));
+    assert!(rendered.contains("what is the resulting frequency & 4 * 3?"));
+    assert!(rendered.contains("    abcde\n    fghij\n"));
+    assert!(rendered.lines().all(|line| line.chars().count() <= 80));
+    assert!(rendered.contains("See the input (/2018/day/1/input)."));
+    assert_eq!(
+        "a [b] c (/x) [d](/y)\n",
+        adventofcodelib::puzzle::render_markdown("a [b] [c](/x) `[d](/y)`", false)
+    );
+}
+
+#[test]
+fn check_find_examples() {
+    let page = r#"<article class="day-desc"><h2>--- Day 1 ---</h2>
+<p>For example, <code>+1, -2, +3, +1</code>:</p><p>the resulting frequency is <code><em>3</em></code>.</p>
+<p><code>+1, +1, +1</code> results in <code> 3</code></p></article>
+<article class="day-desc"><h2>--- Part Two ---</h2>
+<pre><code>#1 @ 1,3: 4x4
+#2 @ 3,1: 4x4
+</code></pre><p>Only <code><em>4</em></code> square inches.</p></article>"#;
+    let examples = adventofcodelib::examples::find_examples(page);
+    let expected = vec![
+        adventofcodelib::examples::ExampleCandidate {
+            part: 1,
+            input: String::from("+1\n-2\n+3\n+1"),
+            answer: Some(String::from("3")),
+        },
+        adventofcodelib::examples::ExampleCandidate {
+            part: 1,
+            input: String::from("+1\n+1\n+1"),
+            answer: None,
+        },
+        adventofcodelib::examples::ExampleCandidate {
+            part: 2,
+            input: String::from("#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4"),
+            answer: Some(String::from("4")),
+        },
+    ];
+    assert_eq!(expected, examples);
+}
+
+fn solve(day: u8, part: u8, file_name: &str) -> String {
+    match (day, part) {
+        (1, 1) => adventofcodelib::day1_task1(file_name).to_string(),
+        (1, 2) => adventofcodelib::day1_task2(file_name)
+            .unwrap()
+            .frequency
+            .to_string(),
+        (2, 1) => adventofcodelib::day2_task1(file_name).to_string(),
+        (2, 2) => adventofcodelib::day2_task2(file_name),
+        (3, 1) => adventofcodelib::day3_task1(file_name).to_string(),
+        (3, 2) => adventofcodelib::day3_task2(file_name).to_string(),
+        (4, 1) => adventofcodelib::day4_task1(file_name).to_string(),
+        (4, 2) => adventofcodelib::day4_task2(file_name).to_string(),
+        (5, 1) => adventofcodelib::day5_task1(file_name).to_string(),
+        (5, 2) => adventofcodelib::day5_task2(file_name).to_string(),
+        _ => panic!("No task {} for day {}", part, day),
+    }
+}
+
+fn check_examples(day: u8, part: u8) {
+    for fixture in
+        adventofcodelib::examples::load_fixtures(adventofcodelib::INPUT_ROOT, day).unwrap()
+    {
+        if fixture.part == part {
+            assert_eq!(
+                fixture.answer,
+                solve(day, fixture.part, &fixture.file_name),
+                "day {} part {} example {}",
+                day,
+                fixture.part,
+                fixture.index
+            );
+        }
+    }
+}
+
+///
+/// Can the real input be read. A configured input key which cannot decrypt the input fails
+/// the test. A missing input is reported, the callers check the examples instead.
+///
+fn real_input_available(file_name: &str) -> bool {
+    use adventofcodelib::store;
+    use std::io::Write;
+    let key = store::InputKey::from_env().unwrap_or_else(|e| panic!("{}", e));
+    if let (Some(_), Err(e)) = (key, store::read_encrypted(file_name)) {
+        panic!("{}", e);
+    }
+    if store::is_available(file_name) {
+        return true;
+    }
+    // the test harness captures `eprintln!`, the notice is written to stderr directly
+    let _ = writeln!(
+        std::io::stderr(),
+        "note: '{}' cannot be read, the examples are checked instead of the real answers",
+        file_name
+    );
+    false
+}
+
+///
+/// Check the answer for the real input. The inputs may be stored encrypted: without
+/// the input key the examples of the task are checked instead.
+///
+fn check_answer(expected: &str, day: u8, part: u8, file_name: &str) {
+    use adventofcodelib::store;
+    if !real_input_available(file_name) {
+        check_examples(day, part);
+        return;
+    }
+    match store::read_encrypted(file_name) {
+        Ok(Some(_)) => {
+            // solve from a copy of the encrypted input without the plain one next to it
+            let dir = std::env::temp_dir().join(format!(
+                "aoc-encrypted-{}-{}-{}",
+                std::process::id(),
+                day,
+                part
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+            let copy = dir.join(std::path::Path::new(file_name).file_name().unwrap());
+            let copy = copy.to_str().unwrap();
+            std::fs::copy(
+                store::encrypted_file_name(file_name),
+                store::encrypted_file_name(copy),
+            )
+            .unwrap();
+            assert_eq!(expected, solve(day, part, copy));
+            std::fs::remove_dir_all(&dir).unwrap();
+        }
+        _ => assert_eq!(expected, solve(day, part, file_name)),
+    }
+}
+
+#[test]
+fn check_example_fixtures() {
+    for day in 1..=5 {
+        for part in 1..=2 {
+            check_examples(day, part);
+        }
+    }
+}
+
+#[test]
+fn check_save_fixture() {
+    use adventofcodelib::examples::{load_fixtures, save_fixture, ExampleCandidate};
+    let root = std::env::temp_dir().join(format!("aoc-fixtures-{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    let root = root.to_str().unwrap();
+    let mut example = ExampleCandidate {
+        part: 1,
+        input: String::from("+1\n-2"),
+        answer: Some(String::from("-1")),
+    };
+    save_fixture(root, 1, &example).unwrap();
+    example.part = 2;
+    save_fixture(root, 1, &example).unwrap();
+    // the confirmed answer replaces the stored one
+    example.answer = Some(String::from("1"));
+    let fixture = save_fixture(root, 1, &example).unwrap();
+    assert_eq!((1, 2), (fixture.index, fixture.part));
+    let answers = load_fixtures(root, 1)
+        .unwrap()
+        .into_iter()
+        .map(|f| (f.index, f.part, f.answer))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        vec![(1, 1, String::from("-1")), (1, 2, String::from("1"))],
+        answers
+    );
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+// 2018-12-01 05:00:00 UTC is 1543640400
+const LEADERBOARD: &str = r#"{"event":"2018","owner_id":"1","members":{
+"1":{"id":"1","name":"alice","local_score":9,"stars":4,"completion_day_level":{
+    "1":{"1":{"get_star_ts":"1543640700"},"2":{"get_star_ts":"1543641000"}},
+    "2":{"1":{"get_star_ts":"1543730400"}}}},
+"2":{"id":2,"name":"bob","local_score":8,"stars":3,"completion_day_level":{
+    "1":{"1":{"get_star_ts":1543640500},"2":{"get_star_ts":1543727000}},
+    "2":{"1":{"get_star_ts":1543727100}}}},
+"3":{"id":3,"name":null,"local_score":0,"stars":0,"completion_day_level":{}}}}"#;
+
+#[test]
+fn check_leaderboard_solve_times() {
+    let board = adventofcodelib::leaderboard::parse_leaderboard(LEADERBOARD).unwrap();
+    assert_eq!(2018, board.year);
+    assert_eq!("(anonymous user #3)", board.members[2].name);
+    let times = adventofcodelib::leaderboard::solve_times(&board);
+    assert_eq!(4, times.len());
+    assert_eq!(("alice", 1), (times[0].member.as_str(), times[0].day));
+    assert_eq!(Some(300), times[0].part1.map(|d| d.num_seconds()));
+    assert_eq!(Some(600), times[0].part2.map(|d| d.num_seconds()));
+    assert_eq!(Some(300), times[0].delta.map(|d| d.num_seconds()));
+    assert_eq!(
+        "1d 00:03:20",
+        adventofcodelib::leaderboard::format_duration(times[1].part2.unwrap())
+    );
+    assert_eq!(None, times[2].part2);
+}
+
+#[test]
+fn check_leaderboard_scores() {
+    let board = adventofcodelib::leaderboard::parse_leaderboard(LEADERBOARD).unwrap();
+    let scores = adventofcodelib::leaderboard::local_scores(&board)
+        .into_iter()
+        .map(|s| (s.member, s.score, s.rank))
+        .collect::<Vec<(String, i64, usize)>>();
+    let expected = vec![
+        (String::from("bob"), 8, 1),
+        (String::from("alice"), 7, 2),
+        (String::from("(anonymous user #3)"), 0, 3),
+    ];
+    assert_eq!(expected, scores);
+    let history = adventofcodelib::leaderboard::ranking_history(&board);
+    assert_eq!(2, history.len());
+    assert_eq!(
+        ("alice", 5),
+        (history[0].1[0].member.as_str(), history[0].1[0].score)
+    );
+    let csv = adventofcodelib::leaderboard::ranking_history_table(&board).to_csv();
+    assert_eq!(
+        "member,day 1,day 2\nbob,1,1\nalice,1,2\n(anonymous user #3),3,3\n",
+        csv
+    );
+}
+
+#[test]
+fn check_leaderboard_same_names() {
+    let json = r#"{"event":"2018","members":{
+"1":{"id":1,"name":"sam","local_score":3,"completion_day_level":{
+    "1":{"1":{"get_star_ts":1543640700}}}},
+"2":{"id":"2","name":"sam","local_score":2,"completion_day_level":{
+    "1":{"1":{"get_star_ts":1543640800}}}},
+"3":{"id":3,"name":null,"local_score":1,"completion_day_level":{
+    "1":{"1":{"get_star_ts":1543640900}}}}}}"#;
+    let board = adventofcodelib::leaderboard::parse_leaderboard(json).unwrap();
+    assert_eq!(
+        "rank,member,score,exported score\n1,sam,3,3\n2,sam,2,2\n3,(anonymous user #3),1,1\n",
+        adventofcodelib::leaderboard::local_scores_table(&board).to_csv()
+    );
+    assert_eq!(
+        "member,day 1\nsam,1\nsam,2\n(anonymous user #3),3\n",
+        adventofcodelib::leaderboard::ranking_history_table(&board).to_csv()
+    );
+    let json = r#"{"event":"2018","members":{"1":{"id":"x","name":null}}}"#;
+    assert!(adventofcodelib::leaderboard::parse_leaderboard(json)
+        .unwrap_err()
+        .contains("cannot parse member id 'x'"));
+    for day in ["0", "32"] {
+        let json = format!(
+            r#"{{"event":"2018","members":{{"1":{{"id":1,"completion_day_level":{{"{}":{{}}}}}}}}}}"#,
+            day
+        );
+        assert_eq!(
+            Err(format!("Cannot parse day '{}' of member 1", day)),
+            adventofcodelib::leaderboard::parse_leaderboard(&json).map(|_| ())
+        );
+    }
+}
+
+///
+/// The solution of the task by its day, part and variant name, not by its place
+/// in the registry.
+///
+fn find_solution(day: u8, part: u8, variant: &str) -> adventofcodelib::solutions::Solution {
+    adventofcodelib::solutions::variants_in(adventofcodelib::INPUT_ROOT)
+        .into_iter()
+        .find(|s| s.day == day && s.part == part && s.variant == variant)
+        .unwrap_or_else(|| panic!("no day {} part {} [{}] solution", day, part, variant))
+}
+
+///
+/// Point the solution to the first example of its day when the real input cannot be read.
+/// Returns whether the real input is used.
+///
+fn use_real_input(solution: &mut adventofcodelib::solutions::Solution) -> bool {
+    if real_input_available(&solution.file_name) {
+        return true;
+    }
+    solution.file_name =
+        adventofcodelib::examples::example_file_name(adventofcodelib::INPUT_ROOT, solution.day, 1);
+    false
+}
+
+#[test]
+fn check_solutions_run() {
+    assert_eq!(10, adventofcodelib::solutions::solutions().len());
+    let mut solution = find_solution(1, 1, adventofcodelib::solutions::DEFAULT_VARIANT);
+    assert_eq!("day1 first task", solution.name());
+    let expected = if use_real_input(&mut solution) {
+        "520"
+    } else {
+        "3"
+    };
+    let report = adventofcodelib::runner::run(&solution, None);
+    assert_eq!(Some(expected), report.answer());
+    let alloc = report.alloc.expect("the counting allocator is installed");
+    assert!(alloc.allocations > 0);
+    let mut solution = find_solution(5, 1, adventofcodelib::solutions::DEFAULT_VARIANT);
+    use_real_input(&mut solution);
+    let bench = adventofcodelib::runner::bench(&solution, 2, None).unwrap();
+    assert!(bench.min <= bench.mean && bench.mean <= bench.max);
+}
+
+#[test]
+fn check_alloc_stats_measure() {
+    let (sum, alloc) = adventofcodelib::alloc_stats::measure(|| vec![1u8; 4096].len());
+    assert_eq!(4096, sum);
+    let alloc = alloc.unwrap();
+    assert!(alloc.allocations >= 1);
+    assert!(alloc.allocated >= 4096);
+    assert_eq!(
+        "3 allocs, 1.5 KiB allocated, 512 B peak",
+        adventofcodelib::alloc_stats::AllocStats {
+            allocations: 3,
+            allocated: 1536,
+            peak: 512,
+        }
+        .to_string()
+    );
+}
+
+#[test]
+fn check_runner_time_limit() {
+    // comparing every pair of 20000 claims takes far longer than the limit
+    let file_name = std::env::temp_dir().join(format!("aoc_claims_{}.txt", std::process::id()));
+    let claims = (0..20_000)
+        .map(|i| format!("#{} @ {},{}: 5x5\n", i + 1, i % 900, i * 7 % 900))
+        .collect::<String>();
+    std::fs::write(&file_name, claims).unwrap();
+    let mut solution = find_solution(3, 2, adventofcodelib::solutions::DEFAULT_VARIANT);
+    assert_eq!("day3 second task", solution.name());
+    solution.file_name = file_name.to_string_lossy().to_string();
+    let limit = std::time::Duration::from_millis(100);
+    let report = adventofcodelib::runner::run(&solution, Some(limit));
+    assert_eq!(
+        adventofcodelib::runner::Outcome::TimedOut(limit),
+        report.outcome
+    );
+    assert_eq!("timed out after 100.00ms", report.outcome.to_string());
+
+    // the cancelled solver stops at the next claim instead of finishing the comparisons
+    let token = adventofcodelib::cancel::CancellationToken::new();
+    token.cancel();
+    let start = std::time::Instant::now();
+    adventofcodelib::cancel::with_token(token, || (solution.solve)(&solution.file_name));
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    std::fs::remove_file(&file_name).unwrap();
+}
+
+#[test]
+fn check_runner_panic() {
+    let mut solution = find_solution(1, 1, adventofcodelib::solutions::DEFAULT_VARIANT);
+    solution.solve = |_| panic!("DAY1: broken");
+    let report = adventofcodelib::runner::run(&solution, None);
+    assert_eq!(
+        adventofcodelib::runner::Outcome::Panicked(String::from("DAY1: broken")),
+        report.outcome
+    );
+}
+
+#[test]
+fn check_cancellation_token() {
+    assert!(!adventofcodelib::cancel::is_cancelled());
+    let token = adventofcodelib::cancel::CancellationToken::new();
+    token.cancel();
+    assert!(adventofcodelib::cancel::with_token(
+        token,
+        adventofcodelib::cancel::is_cancelled
+    ));
+    assert!(!adventofcodelib::cancel::is_cancelled());
+}
+
+#[test]
+fn check_progress_reports() {
+    let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received = reports.clone();
+    let reporter = std::sync::Arc::new(move |phase: &str, done: usize, total: usize| {
+        received
+            .lock()
+            .unwrap()
+            .push((phase.to_string(), done, total));
+    });
+    let mut solution = find_solution(5, 2, adventofcodelib::solutions::DEFAULT_VARIANT);
+    let expected = if use_real_input(&mut solution) {
+        "6336"
+    } else {
+        "4"
+    };
+    let report = adventofcodelib::runner::run_with_progress(&solution, None, Some(reporter));
+    assert_eq!(Some(expected), report.answer());
+    let reports = reports.lock().unwrap();
+    assert_eq!(26, reports.len());
+    assert_eq!((String::from("reacting polymers"), 25, 26), reports[25]);
+}
+
+#[test]
+fn check_config_file() {
+    use adventofcodelib::config::{Config, OutputFormat, SessionSource, Source};
+    let mut config = Config::default();
+    config
+        .apply_toml(
+            "input_root = \"inputs\"\ndefault_year = 2019\noutput_format = \"csv\"\ntimeout = 0\n\
+             [session]\nfile = \"session.txt\"\n",
+            std::path::Path::new("/aoc"),
+        )
+        .unwrap();
+    assert_eq!(
+        std::path::PathBuf::from("/aoc/inputs"),
+        config.input_root.value
+    );
+    assert_eq!(Source::File, config.input_root.source);
+    assert_eq!(2019, config.default_year.value);
+    assert_eq!(OutputFormat::Csv, config.output_format.value);
+    assert_eq!(None, config.timeout.value);
+    assert_eq!(1, config.parallelism.value);
+    assert_eq!(Source::Default, config.parallelism.source);
+    assert_eq!(
+        SessionSource::File(std::path::PathBuf::from("/aoc/session.txt")),
+        config.session.value
+    );
+    assert!(Config::default()
+        .apply_toml("input_rot = \"inputs\"", std::path::Path::new("."))
+        .is_err());
+    assert!(Config::default()
+        .apply_toml("output_format = \"json\"", std::path::Path::new("."))
+        .is_err());
+}
+
+#[test]
+fn check_config_env_overrides_file() {
+    use adventofcodelib::config::{Config, Source};
+    let mut config = Config::default();
+    config
+        .apply_toml(
+            "default_year = 2019\nparallelism = 2",
+            std::path::Path::new("."),
+        )
+        .unwrap();
+    config
+        .apply_env(|name| match name {
+            "AOC_YEAR" => Some(String::from("2020")),
+            "AOC_TIMEOUT" => Some(String::from("5")),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(2020, config.default_year.value);
+    assert_eq!(Source::Env("AOC_YEAR"), config.default_year.source);
+    assert_eq!(
+        Some(std::time::Duration::from_secs(5)),
+        config.timeout.value
+    );
+    assert_eq!(2, config.parallelism.value);
+    assert_eq!(Source::File, config.parallelism.source);
+    assert!(config
+        .apply_env(|name| match name {
+            "AOC_PARALLELISM" => Some(String::from("0")),
+            _ => None,
+        })
+        .is_err());
+    let shown = config.show();
+    assert!(shown.contains("# config file: none"));
+    assert!(shown.contains("default_year  = 2020"));
+    assert!(shown.contains("# $AOC_YEAR"));
+}
+
+#[test]
+fn check_config_discovery() {
+    let root = std::env::temp_dir().join(format!("aoc-config-{}", std::process::id()));
+    let nested = root.join("a").join("b");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(root.join("aoc.toml"), "default_year = 2018\n").unwrap();
+    let no_var = |_: &str| None;
+    assert_eq!(
+        Some(root.join("aoc.toml")),
+        adventofcodelib::config::discover(&nested, no_var)
+    );
+    assert_eq!(
+        Some(std::path::PathBuf::from("/etc/aoc.toml")),
+        adventofcodelib::config::discover(&nested, |name| match name {
+            "AOC_CONFIG" => Some(String::from("/etc/aoc.toml")),
+            _ => None,
+        })
+    );
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn check_embedded_inputs() {
+    let file_name = adventofcodelib::input_file_name("/nonexistent/input_data", 1);
+    let embedded = adventofcodelib::embedded::embedded_input_for(&file_name);
+    if adventofcodelib::embedded::is_enabled() {
+        assert_eq!(
+            std::fs::read_to_string(adventofcodelib::FILE_NAME_DAY1).unwrap(),
+            embedded.unwrap()
+        );
+        assert_eq!(520, adventofcodelib::day1_task1(&file_name));
+        assert_eq!(
+            None,
+            adventofcodelib::embedded::embedded_input_for("/nonexistent/2017/day1_data.txt")
+        );
+    } else {
+        assert_eq!(None, embedded);
+    }
+}
+
+#[test]
+fn check_input_store() {
+    let key = adventofcodelib::store::InputKey::generate();
+    assert_eq!(
+        key.to_hex(),
+        adventofcodelib::store::InputKey::from_hex(&key.to_hex())
+            .unwrap()
+            .to_hex()
+    );
+    assert!(adventofcodelib::store::InputKey::from_hex("abc").is_err());
+    let encrypted = adventofcodelib::store::encrypt(&key, b"+1\n-2\n");
+    assert_ne!(b"+1\n-2\n".to_vec(), encrypted);
+    assert_eq!(
+        b"+1\n-2\n".to_vec(),
+        adventofcodelib::store::decrypt(&key, &encrypted).unwrap()
+    );
+    let other = adventofcodelib::store::InputKey::generate();
+    assert!(adventofcodelib::store::decrypt(&other, &encrypted).is_err());
+    let loaded = adventofcodelib::store::InputKey::load(|name| match name {
+        "AOC_INPUT_KEY" => Some(key.to_hex()),
+        _ => None,
+    })
+    .unwrap();
+    assert!(loaded == Some(key));
+    assert!(adventofcodelib::store::InputKey::load(|_| None)
+        .unwrap()
+        .is_none());
+}
+
+#[derive(Debug, PartialEq, adventofcodelib::line::LinePattern)]
+#[line_pattern("step {name} must be finished before {{{next}}} can begin")]
+struct StepLine {
+    name: char,
+    next: String,
+}
+
+#[test]
+fn check_line_pattern() {
+    assert_eq!(
+        StepLine {
+            name: 'C',
+            next: String::from("A"),
+        },
+        "step C must be finished before {A} can begin"
+            .parse::<StepLine>()
+            .unwrap()
+    );
+    assert_eq!(
+        StepLine {
+            name: 'C',
+            next: String::from("A"),
+        },
+        "step  C must   be finished before {A} can begin"
+            .parse::<StepLine>()
+            .unwrap()
+    );
+}
+
+#[test]
+fn check_line_pattern_errors() {
+    let error = "step CD must be finished before {A} can begin"
+        .parse::<StepLine>()
+        .unwrap_err();
+    assert_eq!(Some("name"), error.field);
+    assert_eq!(6, error.column);
+    assert_eq!(
+        "Cannot parse string 'step CD must be finished before {A} can begin': \
+         invalid name value at column 6: too many characters in string ('CD')",
+        error.to_string()
+    );
+    let error = "step C must be done before {A} can begin"
+        .parse::<StepLine>()
+        .unwrap_err();
+    assert_eq!(None, error.field);
+    assert_eq!(6, error.column);
+    let error = "step C must be finished before {A} can begin!"
+        .parse::<StepLine>()
+        .unwrap_err();
+    assert_eq!(None, error.field);
+    assert_eq!(45, error.column);
+}
+
+#[test]
+fn check_puzzle_title() {
+    let markdown = adventofcodelib::puzzle::puzzle_to_markdown(PUZZLE_PAGE);
+    assert_eq!(
+        Some(String::from("Chronal Calibration")),
+        adventofcodelib::puzzle::puzzle_title(&markdown)
+    );
+    assert_eq!(None, adventofcodelib::puzzle::puzzle_title("no heading"));
+}
+
+#[test]
+fn check_report_table() {
+    let days = vec![adventofcodelib::report::DayReport {
+        year: 2018,
+        day: 1,
+        title: Some(String::from("Chronal Calibration")),
+        stars: 1,
+        times: [Some(std::time::Duration::from_micros(1500)), None],
+        source: String::from("adventofcodelib/src/lib.rs#L10"),
+    }];
+    let table = adventofcodelib::report::markdown_table(&days);
+    assert_eq!(
+        "| 2018 | 1 | [Chronal Calibration](https://adventofcode.com/2018/day/1) | ★ | 1.50ms | - \
+         | [source](adventofcodelib/src/lib.rs#L10) |",
+        table.lines().nth(2).unwrap()
+    );
+}
+
+#[test]
+fn check_report_section() {
+    let table = "| table |\n";
+    assert_eq!(
+        "# title\n\n<!-- report:start -->\n| table |\n<!-- report:end -->\n",
+        adventofcodelib::report::replace_section("# title\n", table).unwrap()
+    );
+    assert_eq!(
+        "# title\n<!-- report:start -->\n| table |\n<!-- report:end -->\nfooter\n",
+        adventofcodelib::report::replace_section(
+            "# title\n<!-- report:start -->\nold\n<!-- report:end -->\nfooter\n",
+            table
+        )
+        .unwrap()
+    );
+    assert!(adventofcodelib::report::replace_section("<!-- report:end -->\n", table).is_err());
+}
+
+#[test]
+fn check_answer_cache() {
+    use adventofcodelib::cache::{fnv1a, AnswerCache, CacheKey, CachedAnswer};
+    assert_eq!(0xcbf2_9ce4_8422_2325, fnv1a(b""));
+    assert_eq!(0xaf63_dc4c_8601_ec8c, fnv1a(b"a"));
+    let mut solution = find_solution(1, 1, adventofcodelib::solutions::DEFAULT_VARIANT);
+    solution.file_name =
+        adventofcodelib::examples::example_file_name(adventofcodelib::INPUT_ROOT, 1, 1);
+    let key = CacheKey::of(&solution).unwrap();
+    assert_eq!(fnv1a(b"+1\n-2\n+3\n+1\n"), key.input_hash);
+    assert_eq!(adventofcodelib::solutions::SOLVER_VERSION, key.version);
+    let file = std::env::temp_dir()
+        .join(format!("aoc-cache-{}", std::process::id()))
+        .join("answers.tsv");
+    let mut cache = AnswerCache::load(&file).unwrap();
+    assert!(cache.is_empty());
+    let old_key = CacheKey {
+        version: String::from("old"),
+        ..key.clone()
+    };
+    cache.insert(
+        old_key.clone(),
+        CachedAnswer {
+            answer: String::from("4"),
+            elapsed: std::time::Duration::from_nanos(1200),
+        },
+    );
+    cache.save().unwrap();
+    let loaded = AnswerCache::load(&file).unwrap();
+    assert_eq!(cache, loaded);
+    let others = loaded.other_versions(&key).collect::<Vec<_>>();
+    assert_eq!(1, others.len());
+    assert_eq!(&old_key, others[0].0);
+
+    // the answer of the current version replaces the old one in the file
+    cache.insert(
+        key.clone(),
+        CachedAnswer {
+            answer: String::from("3"),
+            elapsed: std::time::Duration::from_nanos(1500),
+        },
+    );
+    cache.save().unwrap();
+    let loaded = AnswerCache::load(&file).unwrap();
+    assert_eq!(1, loaded.len());
+    assert_eq!("3", loaded.get(&key).unwrap().answer);
+    assert_eq!(0, loaded.other_versions(&key).count());
+    std::fs::remove_dir_all(file.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn check_explanations() {
+    let example = |day, index| {
+        adventofcodelib::examples::example_file_name(adventofcodelib::INPUT_ROOT, day, index)
+    };
+    assert_eq!(
+        "box ids fghij and fguij differ at position 3 (h vs u) → common letters fgij",
+        adventofcodelib::day2_task2_explain(&example(2, 2))
+    );
+    assert_eq!(
+        "guard #10 slept 50 minutes total; most frequent minute 24 (2 times) → 24×10 = 240",
+        adventofcodelib::day4_task1_explain(&example(4, 1))
+    );
+    assert_eq!(
+        "guard #99 slept at minute 45 more often than any guard at any minute (3 times) → 45×99 = 4455",
+        adventofcodelib::day4_task2_explain(&example(4, 1))
+    );
+    assert_eq!(
+        "removing units c/C leaves the shortest polymer: 4 units (10 without removing any unit)",
+        adventofcodelib::day5_task2_explain(&example(5, 1))
+    );
+    let explained = adventofcodelib::solutions::solutions()
+        .iter()
+        .filter(|s| s.explain.is_some())
+        .map(|s| (s.day, s.part))
+        .collect::<Vec<(u8, u8)>>();
+    assert_eq!(vec![(2, 2), (4, 1), (4, 2), (5, 2)], explained);
+}
+
+#[test]
+fn check_input_stats() {
+    let file_name = adventofcodelib::examples::example_file_name(adventofcodelib::INPUT_ROOT, 1, 1);
+    assert_eq!(
+        adventofcodelib::InputStats {
+            lines: 4,
+            bytes: 12,
+        },
+        adventofcodelib::input_stats(&file_name).unwrap()
+    );
+    assert!(adventofcodelib::input_stats("/nonexistent/day1_data.txt").is_err());
+}
+
+#[test]
+fn check_solution_variants() {
+    let variants = adventofcodelib::solutions::variants_in(adventofcodelib::INPUT_ROOT);
+    let solutions = adventofcodelib::solutions::solutions();
+    assert_eq!(15, variants.len());
+    assert!(solutions
+        .iter()
+        .all(|s| s.variant == adventofcodelib::solutions::DEFAULT_VARIANT));
+    let names = variants
+        .iter()
+        .filter(|s| s.day == 5 && s.part == 2)
+        .map(|s| s.name())
+        .collect::<Vec<String>>();
+    assert_eq!(
+        vec![
+            "day5 second task",
+            "day5 second task [fast]",
+            "day5 second task [parallel]"
+        ],
+        names
+    );
+    let others = variants
+        .into_iter()
+        .filter(|s| s.variant != adventofcodelib::solutions::DEFAULT_VARIANT);
+    for mut variant in others {
+        let default = solutions
+            .iter()
+            .find(|s| (s.year, s.day, s.part) == (variant.year, variant.day, variant.part))
+            .unwrap();
+        let examples =
+            adventofcodelib::examples::load_fixtures(adventofcodelib::INPUT_ROOT, variant.day);
+        for fixture in examples.unwrap_or_default() {
+            assert_eq!(
+                (default.solve)(&fixture.file_name),
+                (variant.solve)(&fixture.file_name),
+                "{} on {}",
+                variant.name(),
+                fixture.file_name
+            );
+        }
+        if use_real_input(&mut variant) {
+            assert_eq!(
+                (default.solve)(&variant.file_name),
+                (variant.solve)(&variant.file_name),
+                "{}",
+                variant.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn check_day1_first_repeat() {
+    use adventofcodelib::{first_repeat, FirstRepeat};
+    assert_eq!(
+        Some(FirstRepeat {
+            frequency: 2,
+            iteration: 1,
+            position: 1,
+        }),
+        first_repeat(&[1, -2, 3, 1])
+    );
+    assert_eq!(
+        Some(FirstRepeat {
+            frequency: 0,
+            iteration: 0,
+            position: 1,
+        }),
+        first_repeat(&[1, -1])
+    );
+    let frequency = |changes: &[i64]| first_repeat(changes).map(|repeat| repeat.frequency);
+    assert_eq!(Some(10), frequency(&[3, 3, 4, -2, -4]));
+    assert_eq!(Some(5), frequency(&[-6, 3, 8, 5, -6]));
+    assert_eq!(Some(14), frequency(&[7, 7, -2, -7, -4]));
+    assert_eq!(Some(-3), frequency(&[-1, -2, 4, -3]));
+    assert_eq!(None, first_repeat(&[1, 1]));
+    let mut solution = find_solution(1, 2, adventofcodelib::solutions::DEFAULT_VARIANT);
+    solution.file_name = String::from("./tests/never_repeating_frequencies.txt");
+    assert_eq!(
+        adventofcodelib::runner::Outcome::Panicked(String::from(
+            "DAY1: no frequency is reached twice!"
+        )),
+        adventofcodelib::runner::run(&solution, None).outcome
+    );
+    assert_eq!(None, first_repeat(&[]));
+}
+
+#[test]
+fn check_frequency_device() {
+    let device = adventofcodelib::frequency::FrequencyDevice::new(vec![1, -2, 3, 1]);
+    assert_eq!(3, device.drift());
+    assert_eq!(
+        vec![1, -1, 2, 3, 4, 2, 5, 6],
+        device.frequencies().take(8).collect::<Vec<i64>>()
+    );
+    assert_eq!(Some(1), device.passes_until_repeat());
+    assert_eq!(Some((0, 0)), device.range(0));
+    assert_eq!(Some((-1, 3)), device.range(1));
+    assert_eq!(Some((-1, 9)), device.range(3));
+    let histogram = device.histogram(2).unwrap();
+    assert_eq!(Some(&2), histogram.get(&2));
+    assert_eq!(Some(&1), histogram.get(&0));
+    assert_eq!(9, histogram.values().sum::<u64>());
+
+    let device = adventofcodelib::frequency::FrequencyDevice::new(vec![-1, -1]);
+    assert_eq!(None, device.passes_until_repeat());
+    assert_eq!(Some((-6, 0)), device.range(3));
+    // too many passes overflow instead of panicking
+    assert_eq!(None, device.range(u64::MAX));
+    assert_eq!(None, device.histogram(u64::MAX));
+    let device = adventofcodelib::frequency::FrequencyDevice::new(vec![i64::MAX, 1]);
+    assert_eq!(None, device.range(1));
+    assert_eq!(None, device.histogram(1));
+    let empty = adventofcodelib::frequency::FrequencyDevice::new(Vec::new());
+    assert_eq!(None, empty.frequencies().next());
+    assert_eq!(None, empty.first_repeat());
+
+    if real_input_available(adventofcodelib::FILE_NAME_DAY1) {
+        let device =
+            adventofcodelib::frequency::FrequencyDevice::load(adventofcodelib::FILE_NAME_DAY1);
+        assert_eq!(520, device.drift());
+        assert_eq!(
+            Some(394),
+            device.first_repeat().map(|repeat| repeat.frequency)
+        );
+    }
+}
+
+#[test]
+fn check_frequency_changes_parser() {
+    use adventofcodelib::{frequency_sum, parse_frequency_changes};
+    assert_eq!(
+        Ok(vec![1, -2, 3, 1]),
+        parse_frequency_changes("+1, -2, +3, +1")
+    );
+    assert_eq!(
+        Ok(vec![13, -7, -17, 12]),
+        parse_frequency_changes("+13\n-7\n\n  -17 \r\n12,\n")
+    );
+    assert_eq!(Ok(Vec::new()), parse_frequency_changes(" \n"));
+    assert_eq!(
+        Err(String::from(
+            "Cannot parse string '+x' at line 2 as a frequency change: invalid digit found in string"
+        )),
+        parse_frequency_changes("+1\n+x")
+    );
+    assert!(parse_frequency_changes("+1 -2").is_err());
+    assert_eq!(
+        Some(3_000_000_000),
+        frequency_sum(&[2_000_000_000, 1_000_000_000])
+    );
+    assert_eq!(None, frequency_sum(&[i64::MAX, 1]));
+}
+
+#[test]
+fn check_day2_near_duplicate() {
+    use adventofcodelib::{find_near_duplicate, NearDuplicate};
+    let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<String>>();
+    assert_eq!(
+        Ok(NearDuplicate {
+            first: String::from("fghij"),
+            second: String::from("fguij"),
+            position: 2,
+            common: String::from("fgij"),
+        }),
+        find_near_duplicate(&ids(&[
+            "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz", "fghij"
+        ]))
+    );
+    assert_eq!(
+        Err(String::from(
+            "There are no box ids differing by exactly one character"
+        )),
+        find_near_duplicate(&ids(&["abcde", "axcye", "abc"]))
+    );
+    assert_eq!(
+        Err(String::from(
+            "Several box ids differ by exactly one character: abc and abd, xyz and xyy"
+        )),
+        find_near_duplicate(&ids(&["abc", "xyz", "abd", "xyy"]))
+    );
+}
+
+#[test]
+fn check_fuzzy_box_ids() {
+    use adventofcodelib::fuzzy::{fuzzy_pairs, levenshtein, BkTree, FuzzyMatch};
+    let chars = |id: &str| id.chars().collect::<Vec<char>>();
+    assert_eq!(3, levenshtein(&chars("kitten"), &chars("sitting")));
+    assert_eq!(0, levenshtein(&chars(""), &chars("")));
+
+    let mut tree = BkTree::new();
+    for id in ["abcde", "abcdf", "abd", "xyzzy", "abcde"] {
+        tree.insert(id);
+    }
+    assert_eq!(4, tree.len());
+    assert_eq!(
+        vec![(0, String::from("abcde")), (1, String::from("abcdf"))],
+        tree.find("abcde", 1)
+    );
+
+    let ids = [
+        "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz", "fgij",
+    ]
+    .iter()
+    .map(|id| id.to_string())
+    .collect::<Vec<String>>();
+    let pair = |distance, first: &str, second: &str| FuzzyMatch {
+        distance,
+        first: first.to_string(),
+        second: second.to_string(),
+    };
+    assert_eq!(
+        vec![
+            pair(1, "fghij", "fgij"),
+            pair(1, "fghij", "fguij"),
+            pair(1, "fgij", "fguij"),
+        ],
+        fuzzy_pairs(&ids, 1)
+    );
+    assert_eq!(
+        vec![
+            pair(1, "fghij", "fgij"),
+            pair(1, "fghij", "fguij"),
+            pair(1, "fgij", "fguij"),
+            pair(2, "abcde", "axcye"),
+        ],
+        fuzzy_pairs(&ids, 2)
+    );
+}
+
+#[test]
+fn check_day2_checksum() {
+    use adventofcodelib::checksum::{Checksum, MultiplicityProfile, Units};
+    let ids = [
+        "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab",
+    ]
+    .iter()
+    .map(|id| id.to_string())
+    .collect::<Vec<String>>();
+    let checksum = Checksum::new(&ids, &[2, 3], Units::Chars);
+    assert_eq!(vec![4, 3], checksum.counts);
+    assert_eq!(Some(12), checksum.value());
+    assert_eq!(
+        std::collections::BTreeMap::from([(1, 1), (2, 1), (3, 1)]),
+        checksum.profiles[1].letters
+    );
+    let checksum = Checksum::new(&ids, &[1, 2, 3, 4], Units::Chars);
+    assert_eq!(vec![6, 4, 3, 0], checksum.counts);
+    assert_eq!(Some(0), checksum.value());
+
+    // 'é' is two bytes, the first one is the same as the first byte of 'è'
+    let profile = MultiplicityProfile::of("éè", Units::Chars);
+    assert!(profile.has(1) && !profile.has(2));
+    let profile = MultiplicityProfile::of("éè", Units::Bytes);
+    assert!(profile.has(2));
+}
+
+#[test]
+fn check_day3_fabric() {
+    use adventofcodelib::fabric::Fabric;
+    use adventofcodelib::Inch;
+    let inches = [
+        Inch::new(1, 1, 3, 4, 4),
+        Inch::new(2, 3, 1, 4, 4),
+        Inch::new(3, 5, 5, 2, 2),
+    ];
+    let fabric = Fabric::new(&inches);
+    assert!(!fabric.is_sparse());
+    assert_eq!((1, 1, 6, 6), fabric.bounds());
+    assert_eq!(4, fabric.overlap_area());
+    assert_eq!(2, fabric.claims_at(3, 3));
+    assert_eq!(1, fabric.claims_at(5, 5));
+    assert_eq!(0, fabric.claims_at(0, 0));
+    assert_eq!(0, fabric.claims_at(100, 100));
+
+    // far apart claims past the old 1000×1000 fabric
+    let inches = [
+        Inch::new(1, u32::MAX - 1, 5, 2, 2),
+        Inch::new(2, u32::MAX, 6, 3, 1),
+        Inch::new(3, 0, 0, 1, 1),
+    ];
+    let fabric = Fabric::new(&inches);
+    assert!(fabric.is_sparse());
+    assert_eq!(1, fabric.overlap_area());
+    assert_eq!(2, fabric.claims_at(u64::from(u32::MAX), 6));
+    assert_eq!(1, fabric.claims_at(u64::from(u32::MAX) + 2, 6));
+    assert_eq!(0, Fabric::new(&[]).overlap_area());
+
+    // the sparse fabric does not depend on the claim area
+    let fabric = Fabric::new(&[
+        Inch::new(1, 0, 0, 1_000_000, 1_000_000),
+        Inch::new(2, 500_000, 500_000, 1_000_000, 1_000_000),
+    ]);
+    assert!(fabric.is_sparse());
+    assert_eq!(250_000_000_000, fabric.overlap_area());
+    assert_eq!(1_500_000_000_000, fabric.area_exactly(1));
+    assert_eq!(2, fabric.claims_at(999_999, 500_000));
+    assert_eq!(0, fabric.claims_at(1_000_000, 0));
+    assert_eq!(0, fabric.claims_at(1_500_000, 0));
+
+    // many small claims far apart keep the memory of the claims only
+    let inches = (0..20_000u32)
+        .map(|i| {
+            let (left, top) = (i * 7919 % 1_000_003, i * 104_729 % 999_983);
+            Inch::new(i + 1, left * 4, top * 4, 5, 5)
+        })
+        .collect::<Vec<Inch>>();
+    let (fabric, alloc) = adventofcodelib::alloc_stats::measure(|| Fabric::new(&inches));
+    assert!(fabric.is_sparse());
+    assert!(alloc.unwrap().peak < 64 << 20);
+    assert_eq!(
+        adventofcodelib::sweep::overlap_area(&inches),
+        fabric.overlap_area()
+    );
+    assert_eq!(20_000 * 25, fabric.area_at_least(1) + fabric.overlap_area());
+}
+
+#[test]
+fn check_day3_sweep() {
+    use adventofcodelib::sweep::{intact_claims, overlap_area};
+    use adventofcodelib::Inch;
+    let inches = [
+        Inch::new(1, 1, 3, 4, 4),
+        Inch::new(2, 3, 1, 4, 4),
+        Inch::new(3, 5, 5, 2, 2),
+    ];
+    assert_eq!(4, overlap_area(&inches));
+    assert_eq!(vec![3], intact_claims(&inches));
+
+    // three claims over the same square count once, touching claims do not overlap
+    let inches = [
+        Inch::new(1, 0, 0, 3, 3),
+        Inch::new(2, 1, 1, 1, 1),
+        Inch::new(3, 1, 1, 3, 3),
+        Inch::new(4, 4, 0, 2, 1),
+        Inch::new(5, u32::MAX, u32::MAX, 2, 2),
+    ];
+    let fabric = adventofcodelib::fabric::Fabric::new(&inches);
+    assert_eq!(fabric.overlap_area(), overlap_area(&inches));
+    assert_eq!(4, overlap_area(&inches));
+    assert_eq!(vec![4, 5], intact_claims(&inches));
+    assert_eq!(0, overlap_area(&[]));
+    assert!(intact_claims(&[]).is_empty());
+
+    // the claims all spanning the fabric, only the last two overlap
+    let mut inches = (0..50_000)
+        .map(|i| Inch::new(i + 1, 0, 2 * i, 1_000_000, 1))
+        .collect::<Vec<Inch>>();
+    inches.push(Inch::new(50_001, 10, 99_998, 1, 5));
+    let intact = intact_claims(&inches);
+    assert_eq!(49_999, intact.len());
+    assert!(!intact.contains(&50_000) && !intact.contains(&50_001));
+
+    // the same as comparing every pair
+    let claims = (0..300u32)
+        .map(|i| (i + 1, i * 37 % 101, i * 53 % 97, 1 + i % 7, 1 + i * 3 % 5))
+        .collect::<Vec<(u32, u32, u32, u32, u32)>>();
+    let overlap = |a: &(u32, u32, u32, u32, u32), b: &(u32, u32, u32, u32, u32)| {
+        a.1 < b.1 + b.3 && b.1 < a.1 + a.3 && a.2 < b.2 + b.4 && b.2 < a.2 + a.4
+    };
+    let expected = claims
+        .iter()
+        .filter(|a| claims.iter().all(|b| a.0 == b.0 || !overlap(a, b)))
+        .map(|a| a.0)
+        .collect::<Vec<u32>>();
+    let inches = claims
+        .iter()
+        .map(|&(id, left, top, wide, toll)| Inch::new(id, left, top, wide, toll))
+        .collect::<Vec<Inch>>();
+    assert!(!expected.is_empty() && expected.len() < claims.len());
+    assert_eq!(expected, intact_claims(&inches));
+}
+
+#[test]
+fn check_day3_coverage_queries() {
+    use adventofcodelib::fabric::{Fabric, Region};
+    use adventofcodelib::Inch;
+    let inches = [
+        Inch::new(1, 0, 0, 3, 3),
+        Inch::new(2, 1, 1, 1, 1),
+        Inch::new(3, 1, 1, 3, 3),
+        Inch::new(4, 4, 0, 2, 1),
+    ];
+    // the far claim makes the fabric sparse
+    let far = Inch::new(5, 5000, 5000, 1, 1);
+    for (fabric, far_area) in [
+        (Fabric::new(&inches), 0),
+        (Fabric::new(&[&inches[..], &[far]].concat()), 1),
+    ] {
+        assert_eq!(10 + 2 + far_area, fabric.area_exactly(1));
+        assert_eq!(3, fabric.area_exactly(2));
+        assert_eq!(1, fabric.area_exactly(3));
+        assert_eq!(0, fabric.area_exactly(0));
+        assert_eq!(4, fabric.area_at_least(2));
+        assert_eq!(fabric.overlap_area(), fabric.area_at_least(2));
+        assert_eq!(
+            (
+                3,
+                vec![Region {
+                    xs: 1..2,
+                    ys: 1..2,
+                    count: 3
+                }]
+            ),
+            fabric.max_depth()
+        );
+        assert_eq!(vec![1, 2, 3], fabric.claims_covering(1, 1));
+        assert_eq!(vec![4], fabric.claims_covering(5, 0));
+        assert!(fabric.claims_covering(3, 0).is_empty());
+    }
+    let fabric = Fabric::new(&inches);
+    assert_eq!(fabric.area_exactly(1) + 4, fabric.area_at_least(1));
+    let regions = fabric.claimed_regions();
+    assert_eq!(
+        Region {
+            xs: 0..3,
+            ys: 0..1,
+            count: 1
+        },
+        regions[0]
+    );
+    assert_eq!(
+        fabric.area_at_least(1),
+        regions.iter().map(Region::area).sum::<u64>()
+    );
+    assert_eq!(
+        vec![(0, 0), (1, 0), (2, 0)],
+        regions[0].squares().collect::<Vec<_>>()
+    );
+    assert_eq!((0, Vec::new()), Fabric::new(&[]).max_depth());
+
+    // the deepest part of the huge claims is a single region
+    let fabric = Fabric::new(&[
+        Inch::new(1, 0, 0, 100_000, 100_000),
+        Inch::new(2, 10, 10, 50_000, 50_000),
+    ]);
+    assert_eq!(
+        (
+            2,
+            vec![Region {
+                xs: 10..50_010,
+                ys: 10..50_010,
+                count: 2
+            }]
+        ),
+        fabric.max_depth()
+    );
+    assert_eq!(
+        10_000_000_000,
+        fabric
+            .claimed_regions()
+            .iter()
+            .map(Region::area)
+            .sum::<u64>()
+    );
+}
+
+#[test]
+fn check_day3_render() {
+    use adventofcodelib::fabric::Fabric;
+    use adventofcodelib::render::{ascii, picture};
+    use adventofcodelib::Inch;
+    let fabric = Fabric::new(&[
+        Inch::new(1, 1, 3, 4, 4),
+        Inch::new(2, 3, 1, 4, 4),
+        Inch::new(3, 5, 5, 2, 2),
+    ]);
+    assert_eq!(
+        Ok(String::from(
+            "........\n...2222.\n...2222.\n.11XX22.\n.11XX22.\n.111133.\n.111133.\n........\n"
+        )),
+        ascii(&fabric)
+    );
+    let picture = picture(&fabric).unwrap();
+    assert_eq!((8, 8), (picture.width, picture.height));
+    // the overlap is highlighted, the intact claim 3 is outlined
+    assert_eq!(picture.pixels[3 * 8 + 3], picture.pixels[4 * 8 + 4]);
+    assert_ne!(picture.pixels[3 * 8 + 3], picture.pixels[3 * 8 + 1]);
+    assert_eq!([255, 255, 255], picture.pixels[5 * 8 + 5]);
+    assert_eq!(picture.pixels[0], picture.pixels[7 * 8 + 7]);
+
+    let ppm = picture.to_ppm();
+    assert!(ppm.starts_with(b"P6\n8 8\n255\n"));
+    assert_eq!(11 + 8 * 8 * 3, ppm.len());
+    let png = picture.to_png();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x08\0\0\0\x08"));
+    assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+
+    let far = Fabric::new(&[Inch::new(1, 0, 0, 1, 1), Inch::new(2, 500, 0, 1, 1)]);
+    assert!(ascii(&far).is_err());
+    assert!(adventofcodelib::render::write(&far, std::path::Path::new("day3.bmp")).is_err());
+}